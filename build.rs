@@ -0,0 +1,29 @@
+//! Compresses the bundled stopword list, sentiment and expression lexicons and gazetteer so they can
+//! be embedded into the library when the `embedded-resources` feature is enabled.
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+const RESOURCES: [&str; 4] = ["stopwords.csv", "SWNetTR.csv", "expressions.csv", "gazetteer.csv"];
+
+fn main() {
+    for name in RESOURCES {
+        println!("cargo:rerun-if-changed=files/{}", name);
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBEDDED_RESOURCES").is_none() {
+        return;
+    }
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    for name in RESOURCES {
+        let data = fs::read(Path::new("files").join(name)).expect("bundled resource is readable");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&data).expect("compressing into memory cannot fail");
+        let compressed = encoder.finish().expect("compressing into memory cannot fail");
+        fs::write(Path::new(&out_dir).join(format!("{}.gz", name)), compressed).expect("OUT_DIR is writable");
+    }
+}
@@ -1,5 +1,5 @@
 use rustysozluk::{fetch_user, tokio};
-use rustysozluk::analyzer::analyzer::top_words;
+use rustysozluk::analyzer::analyzer::{print_top_words, read_stopwords, top_words, export_top_words_to_csv};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -7,9 +7,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let entry_number = 4;
     let output_csv = false;
     let entries = fetch_user(username, entry_number).await?;
-    top_words(entries, "files/stopwords.csv", 5,output_csv)?;  // stopwords file path, top N words. False means output is not csv.
+    let stopwords = read_stopwords("files/stopwords.csv")?; // stopwords file path
+    let words = top_words(&entries, &stopwords, 5); // top N words, most frequent first
+    if output_csv {
+        export_top_words_to_csv(&words, "top_words.csv")?;
+        println!("CSV file generated: top_words.csv");
+    } else {
+        print_top_words(&words);
+    }
     Ok(())
 }
-
-
-
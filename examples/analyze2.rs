@@ -2,7 +2,8 @@
 // Bu örnek, analyze.rs'den daha gelişmiştir.
 
 use rustysozluk::{fetch_user, tokio};
-use rustysozluk::analyzer::analyzer::{read_stopwords, word_frequencies};
+use rustysozluk::analyzer::analyzer::word_frequencies;
+use rustysozluk::analyzer::resources::default_stopwords;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -10,10 +11,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let entry_number = 4;
     let entries = fetch_user(username, entry_number).await?;
     
-    let stopwords = read_stopwords("files/stopwords.csv")?;  // stopwords file path
+    let stopwords = default_stopwords();  // bundled stopwords, use read_stopwords for your own file //gömülü stopword listesi
 
     // Count word frequencies in the entries // kelime frekansını çıkart
-    let word_count: std::collections::HashMap<String, usize> = word_frequencies(entries.iter().map(|e| e.content.clone()).collect(), &stopwords);
+    let word_count: std::collections::HashMap<String, usize> = word_frequencies(entries.iter().map(|e| e.content.clone()).collect(), stopwords);
     
     // Sort the HashMap by value (frequency) in descending order // HashMapi azalana göre  sırala
     let mut word_vec: Vec<(&String, &usize)> = word_count.iter().collect();
@@ -9,7 +9,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let title = "https://eksisozluk.com/rust-programlama-dili--5575227"; // title URL //başlık URL'si
     let number_of_entries = 4; // number of entries to fetch //alınacak girdi sayısı
     let entries = fetch_title(title, number_of_entries).await?;
-    analyze_sentiment(entries)?;
+    let report = analyze_sentiment(entries)?;
+    println!("{}", report);
     Ok(())
 
 }
\ No newline at end of file
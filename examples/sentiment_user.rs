@@ -9,6 +9,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let username = "morgomir"; // username //kullanıcı adı
     let entry_number = 4; // number of entries to fetch //alınacak girdi sayısı
     let entries = fetch_user(username, entry_number).await?;
-    analyze_sentiment(entries)?; // analyze sentiment //duygu durumunu analiz et
+    let report = analyze_sentiment(entries)?; // analyze sentiment //duygu durumunu analiz et
+    println!("{}", report); // print the summary table //özet tabloyu yazdır
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+//this example stores the entries of a title in a SQLite database. Run it with `--features sqlite`.
+//bu örnek bir başlıktaki girdileri SQLite veritabanına kaydeder. `--features sqlite` ile çalıştırın.
+use rustysozluk::{fetch_title_into, tokio};
+use rustysozluk::storage::SqliteStore;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = SqliteStore::open("sozluk.db")?; // database file //veritabanı dosyası
+    let title = "https://eksisozluk.com/rust-programlama-dili--5575227"; // title URL //başlık URL'si
+    fetch_title_into(title, 10, &mut store).await?; // scraping again updates the stored entries //tekrar çekmek kayıtlı girdileri günceller
+    for name in store.titles()? {
+        println!("{}: {} entries", name, store.entries_for_title(&name)?.len());
+    }
+    Ok(())
+}
@@ -1,14 +1,20 @@
 use std::collections::HashSet;
 use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::{self, BufRead, Read, Write};
 use std::path::Path;
 use std::collections::HashMap;
 use crate::parser::Entry;
+#[cfg(not(feature = "embedded-resources"))]
+use crate::analyzer::sentiment::read_expression_lexicon;
+use crate::analyzer::sentiment::{SentimentAnalyzer, SentimentReport};
+use crate::analyzer::stemmer::Stemmer;
+use crate::analyzer::tokenizer::{turkish_lowercase, TokenKind, Tokenizer};
 use csv::Writer;
 use csv::Reader;
+use serde::{Deserialize, Serialize};
 
 /// Reads stopwords from a CSV file and returns them as a HashSet.
-/// Each line in the CSV file represents a stopword.
+/// Each line in the CSV file represents a stopword; stopwords are lowercased with Turkish rules.
 /// Stopwords are commonly used words that are often filtered out before
 /// textual data processing.
 ///
@@ -20,23 +26,41 @@ use csv::Reader;
 ///
 /// A `HashSet<String>` containing the stopwords.
 pub fn read_stopwords(file_path: &str) -> io::Result<HashSet<String>> {
-    let mut stopwords = HashSet::new();
     let path = Path::new(file_path);
-    let file = File::open(&path)?;
-    let reader = io::BufReader::new(file);
+    let file = File::open(path)?;
+    read_stopwords_from(file)
+}
+
+/// Reads stopwords from any reader, one stopword per line, like `read_stopwords`.
+///
+/// # Arguments
+///
+/// * `reader` - The source implementing `Read`.
+///
+/// # Returns
+///
+/// A `HashSet<String>` containing the stopwords.
+pub fn read_stopwords_from<R: Read>(reader: R) -> io::Result<HashSet<String>> {
+    let mut stopwords = HashSet::new();
+    let reader = io::BufReader::new(reader);
 
     for line in reader.lines() {
         let line = line?;
         let word: Vec<&str> = line.split(',').collect(); 
-        stopwords.insert(word[0].to_string()); 
+        let word = word[0].trim_start_matches('\u{feff}').trim();
+        if !word.is_empty() {
+            stopwords.insert(turkish_lowercase(word));
+        }
     }
     Ok(stopwords)
 }
 
 
 /// Preprocesses the given text.
-/// The function lowercases the text, removes non-alphabetic characters,
-/// and filters out stopwords.
+/// The function tokenizes the text with the Turkish-aware `Tokenizer`, which lowercases
+/// with Turkish rules (`I` → `ı`, `İ` → `i`) and splits apostrophe suffixes
+/// (`Türkiye'nin` → `türkiye`). Words, hashtags, mentions and emoji are kept; numbers,
+/// URLs and punctuation are removed, and stopwords are filtered out.
 ///
 /// # Arguments
 ///
@@ -47,14 +71,28 @@ pub fn read_stopwords(file_path: &str) -> io::Result<HashSet<String>> {
 ///
 /// A `String` containing the preprocessed text.
 pub fn preprocess_text(text: &str, stopwords: &HashSet<String>) -> String {
-    text.to_lowercase()
-        .chars()
-        .filter(|&c| c.is_alphabetic() || c.is_whitespace())
-        .collect::<String>()
-        .split_whitespace()
-        .filter(|&word| !stopwords.contains(word))
-        .collect::<Vec<&str>>()
-        .join(" ")
+    preprocess_tokens(text, stopwords).join(" ")
+}
+
+/// Token kinds kept by `preprocess_text` and `preprocess_tokens`.
+pub const CONTENT_TOKEN_KINDS: [TokenKind; 4] = [TokenKind::Word, TokenKind::Hashtag, TokenKind::Mention, TokenKind::Emoji];
+
+/// Preprocesses the given text like `preprocess_text` but returns the tokens as a vector.
+///
+/// # Arguments
+///
+/// * `text` - A string slice containing the text to preprocess.
+/// * `stopwords` - A reference to a HashSet containing stopwords.
+///
+/// # Returns
+///
+/// A `Vec<String>` containing the remaining tokens in order.
+pub fn preprocess_tokens(text: &str, stopwords: &HashSet<String>) -> Vec<String> {
+    Tokenizer::new()
+        .terms(text, &CONTENT_TOKEN_KINDS)
+        .into_iter()
+        .filter(|word| !stopwords.contains(word))
+        .collect()
 }
 
 /// Counts the frequency of each word in the given vector of strings.
@@ -70,12 +108,31 @@ pub fn preprocess_text(text: &str, stopwords: &HashSet<String>) -> String {
 ///
 /// A `HashMap` where the keys are words and the values are their frequencies.
 pub fn word_frequencies(entries: Vec<String>, stopwords: &HashSet<String>) -> HashMap<String, usize> {
+    word_frequencies_with(entries, stopwords, None)
+}
+
+/// Counts the frequency of each word like `word_frequencies`, optionally stemming words first.
+/// With a stemmer, inflected forms such as "kitaplar" and "kitabı" are counted together as "kitap".
+///
+/// # Arguments
+///
+/// * `entries` - A vector of strings representing the entries to analyze.
+/// * `stopwords` - A reference to a HashSet containing stopwords.
+/// * `stemmer` - An optional `Stemmer` applied to every word.
+///
+/// # Returns
+///
+/// A `HashMap` where the keys are words (or stems) and the values are their frequencies.
+pub fn word_frequencies_with(entries: Vec<String>, stopwords: &HashSet<String>, stemmer: Option<&Stemmer>) -> HashMap<String, usize> {
     let mut word_count: HashMap<String, usize> = HashMap::new();
 
     for entry in entries {
-        let processed_entry = preprocess_text(&entry, stopwords);
-        for word in processed_entry.split_whitespace() {
-            *word_count.entry(word.to_string()).or_insert(0) += 1;
+        for word in preprocess_tokens(&entry, stopwords) {
+            let word = match stemmer {
+                Some(stemmer) => stemmer.stem(&word),
+                None => word,
+            };
+            *word_count.entry(word).or_insert(0) += 1;
         }
     }
 
@@ -84,51 +141,180 @@ pub fn word_frequencies(entries: Vec<String>, stopwords: &HashSet<String>) -> Ha
 
 
 
-/// Analyzes the given entries and displays the top N most frequent words.
-/// The function displays the top N most frequent words in the given text entries
-/// and has an option to output the result as a CSV file.
+/// Filters and stemming applied by `top_words_with`.
+#[derive(Debug, Clone)]
+pub struct TopWordsOptions {
+    /// Shortest word (in characters) that is counted.
+    pub min_length: usize,
+    /// Longest word (in characters) that is counted.
+    pub max_length: usize,
+    /// Stemmer applied to every word before counting, `None` to count words as written.
+    pub stemmer: Option<Stemmer>,
+}
+
+impl Default for TopWordsOptions {
+    fn default() -> Self {
+        TopWordsOptions {
+            min_length: 1,
+            max_length: usize::MAX,
+            stemmer: None,
+        }
+    }
+}
+
+/// Returns the N most frequent words of the given entries.
+/// Words with the same frequency are ordered alphabetically, so the result is deterministic.
+/// Use `print_top_words`, `write_top_words_csv` or `write_top_words_json` to output the result.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` structs representing the entries to analyze.
+/// * `stopwords` - A reference to a HashSet containing stopwords, e.g. from `read_stopwords`.
+/// * `n` - The number of top frequent words to return.
+///
+/// # Returns
+///
+/// A `Vec<(String, usize)>` of words and their frequencies, most frequent first.
+pub fn top_words(entries: &[Entry], stopwords: &HashSet<String>, n: usize) -> Vec<(String, usize)> {
+    top_words_with(entries, stopwords, n, &TopWordsOptions::default())
+}
+
+/// Returns the N most frequent words like `top_words`, with word-length filters and optional stemming.
 ///
 /// # Arguments
 ///
-/// * `entries` - A vector of `Entry` structs representing the entries to analyze.
-/// * `stopwords_file` - The path to the stopwords file.
-/// * `n` - The number of top frequent words to display.
-/// * `output_csv` - A boolean value that determines whether the output should be in CSV format.
+/// * `entries` - A slice of `Entry` structs representing the entries to analyze.
+/// * `stopwords` - A reference to a HashSet containing stopwords.
+/// * `n` - The number of top frequent words to return.
+/// * `options` - The `TopWordsOptions` to apply.
+///
+/// # Returns
+///
+/// A `Vec<(String, usize)>` of words and their frequencies, most frequent first.
+pub fn top_words_with(entries: &[Entry], stopwords: &HashSet<String>, n: usize, options: &TopWordsOptions) -> Vec<(String, usize)> {
+    top_words_in(entries.iter().map(|e| e.content.clone()).collect(), stopwords, n, options)
+}
+
+/// Ranks the words of the given texts like `top_words_with`.
+pub(crate) fn top_words_in(contents: Vec<String>, stopwords: &HashSet<String>, n: usize, options: &TopWordsOptions) -> Vec<(String, usize)> {
+    let mut word_vec: Vec<(String, usize)> = word_frequencies_with(contents, stopwords, options.stemmer.as_ref())
+        .into_iter()
+        .filter(|(word, _)| (options.min_length..=options.max_length).contains(&word.chars().count()))
+        .collect();
+    word_vec.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    word_vec.truncate(n);
+    word_vec
+}
+
+/// Formats ranked words as a text table with the columns `Rank`, `Word` and `Frequency`.
+///
+/// # Arguments
+///
+/// * `words` - The ranked words, as returned by `top_words`.
+///
+/// # Returns
+///
+/// A `String` containing the table.
+pub fn top_words_table(words: &[(String, usize)]) -> String {
+    let width = words.iter().map(|(word, _)| word.chars().count()).max().unwrap_or(0).max(15);
+    let mut table = format!("{:<10} {:<width$} Frequency\n", "Rank", "Word", width = width);
+    table.push_str(&"-".repeat(width + 20));
+    for (idx, (word, freq)) in words.iter().enumerate() {
+        table.push_str(&format!("\n{:<10} {:<width$} {}", idx + 1, word, freq, width = width));
+    }
+    table
+}
+
+/// Prints ranked words as a table to stdout.
+///
+/// # Arguments
+///
+/// * `words` - The ranked words, as returned by `top_words`.
+pub fn print_top_words(words: &[(String, usize)]) {
+    println!("Top {} most frequent words:", words.len());
+    println!("{}", top_words_table(words));
+}
+
+/// Writes ranked words as CSV with the columns `Rank`, `Word` and `Frequency`.
+///
+/// # Arguments
+///
+/// * `words` - The ranked words, as returned by `top_words`.
+/// * `writer` - The destination implementing `Write`.
 ///
 /// # Returns
 ///
 /// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
-pub fn top_words(entries: Vec<Entry>, stopwords_file: &str, n: usize, output_csv: bool) -> io::Result<()> {
-    let entry_contents: Vec<String> = entries.into_iter().map(|e| e.content).collect();
-    let stopwords = read_stopwords(stopwords_file)?;
-    let word_count = word_frequencies(entry_contents, &stopwords);
-    let mut word_vec: Vec<(&String, &usize)> = word_count.iter().collect();
-    word_vec.sort_by(|a, b| b.1.cmp(a.1));
-
-    if output_csv {
-        let mut wtr = Writer::from_path("top_words.csv")?;
-        wtr.write_record(&["Rank", "Word", "Frequency"])?;
-        for (idx, (word, freq)) in word_vec.iter().enumerate().take(n) {
-            wtr.write_record(&[(idx + 1).to_string(), word.to_string(), freq.to_string()])?;
-        }
-        wtr.flush()?;
-        println!("CSV file generated: top_words.csv");
-    } else {
-        println!("Top {} most frequent words:", n);
-        println!("{:<10} {:<15} {}", "Rank", "Word", "Frequency");
-        println!("{}", "-".repeat(30));
-        for (idx, (word, freq)) in word_vec.iter().enumerate().take(n) {
-            println!("{:<10} {:<15} {}", idx + 1, word, freq);
-        }
+pub fn write_top_words_csv<W: Write>(words: &[(String, usize)], writer: W) -> io::Result<()> {
+    let mut wtr = Writer::from_writer(writer);
+    wtr.write_record(["Rank", "Word", "Frequency"])?;
+    for (idx, (word, freq)) in words.iter().enumerate() {
+        wtr.write_record(&[(idx + 1).to_string(), word.to_string(), freq.to_string()])?;
     }
+    wtr.flush()
+}
+
+/// Writes ranked words to a CSV file like `write_top_words_csv`.
+///
+/// # Arguments
+///
+/// * `words` - The ranked words, as returned by `top_words`.
+/// * `file_name` - The path of the CSV file to create.
+///
+/// # Returns
+///
+/// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+pub fn export_top_words_to_csv(words: &[(String, usize)], file_name: &str) -> io::Result<()> {
+    write_top_words_csv(words, File::create(file_name)?)
+}
+
+#[derive(Serialize)]
+struct RankedWord<'a> {
+    rank: usize,
+    word: &'a str,
+    frequency: usize,
+}
+
+/// Writes ranked words as a JSON array of `{"rank", "word", "frequency"}` objects.
+///
+/// # Arguments
+///
+/// * `words` - The ranked words, as returned by `top_words`.
+/// * `writer` - The destination implementing `Write`.
+///
+/// # Returns
+///
+/// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+pub fn write_top_words_json<W: Write>(words: &[(String, usize)], writer: W) -> io::Result<()> {
+    let ranked: Vec<RankedWord> = words
+        .iter()
+        .enumerate()
+        .map(|(idx, (word, frequency))| RankedWord { rank: idx + 1, word, frequency: *frequency })
+        .collect();
+    serde_json::to_writer_pretty(writer, &ranked)?;
     Ok(())
 }
 
+/// Writes ranked words to a JSON file like `write_top_words_json`.
+///
+/// # Arguments
+///
+/// * `words` - The ranked words, as returned by `top_words`.
+/// * `file_name` - The path of the JSON file to create.
+///
+/// # Returns
+///
+/// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+pub fn export_top_words_to_json(words: &[(String, usize)], file_name: &str) -> io::Result<()> {
+    write_top_words_json(words, File::create(file_name)?)
+}
 
-#[derive(Debug)]
- pub struct Sentiment {
-    tone: f32,
-    polarity: i32,
+
+/// A lexicon entry: the tone of a word between -1 and 1 and its polarity (`1`, `0` or `-1`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Sentiment {
+    pub tone: f32,
+    pub polarity: i32,
 }
 
 
@@ -143,10 +329,22 @@ pub fn top_words(entries: Vec<Entry>, stopwords_file: &str, n: usize, output_csv
 /// # Returns
 ///
 /// A `HashMap<String, Sentiment>` containing the sentiment lexicon.
-
 pub fn read_sentiment_lexicon(file_path: &str) -> io::Result<HashMap<String, Sentiment>> {
+    read_sentiment_lexicon_from(File::open(file_path)?)
+}
+
+/// Reads a sentiment lexicon from any reader, in the same `ID,WORD,TONE,POLARITY` format as `read_sentiment_lexicon`.
+///
+/// # Arguments
+///
+/// * `reader` - The source implementing `Read`.
+///
+/// # Returns
+///
+/// A `HashMap<String, Sentiment>` containing the sentiment lexicon.
+pub fn read_sentiment_lexicon_from<R: Read>(reader: R) -> io::Result<HashMap<String, Sentiment>> {
     let mut lexicon = HashMap::new();
-    let mut rdr = Reader::from_path(file_path)?;
+    let mut rdr = Reader::from_reader(reader);
     for result in rdr.records() {
         let record = result?;
         let word = &record[1];
@@ -159,9 +357,42 @@ pub fn read_sentiment_lexicon(file_path: &str) -> io::Result<HashMap<String, Sen
 }
 
 
+/// Looks up a word in a sentiment lexicon, optionally falling back to its stems.
+/// The exact word is tried first; with a stemmer, the progressively stripped forms of the
+/// word are tried next, so "güzeldi" and "güzelliği" can match the lexicon entry for "güzel".
+///
+/// # Arguments
+///
+/// * `lexicon` - A reference to the sentiment lexicon.
+/// * `word` - A lowercased word.
+/// * `stemmer` - An optional `Stemmer` used when the exact word is not in the lexicon.
+///
+/// # Returns
+///
+/// The matching lexicon key and its `Sentiment`, or `None` if neither the word nor any of its stems is in the lexicon.
+pub fn lookup_sentiment<'a>(lexicon: &'a HashMap<String, Sentiment>, word: &str, stemmer: Option<&Stemmer>) -> Option<(&'a str, &'a Sentiment)> {
+    if let Some((key, sentiment)) = lexicon.get_key_value(word) {
+        return Some((key, sentiment));
+    }
+    stemmer?
+        .candidates(word)
+        .iter()
+        .skip(1)
+        .find_map(|candidate| lexicon.get_key_value(candidate))
+        .map(|(key, sentiment)| (key.as_str(), sentiment))
+}
+
 /// Performs sentiment analysis on the given text entries.
-/// The function performs sentiment analysis on the given text entries and prints
-/// the results in a formatted manner.
+/// The function scores every entry with the SWNetTR lexicon and the lexicon of emoticons, emoji
+/// and slang, and returns a `SentimentReport`
+/// with per-entry scores and corpus totals. Print the report with `println!("{}", report)`
+/// to get the summary table. Words missing from the lexicon are looked up by their stems;
+/// use `analyze_sentiment_with` to turn stemming off.
+///
+/// With the default `embedded-resources` feature the bundled lexicons and stopwords are used;
+/// without it they are read from the `files/` folder of the working directory on every call, and a
+/// missing `files/expressions.csv` leaves the expression lexicon empty.
+/// Build a `SentimentAnalyzer` to use other resources.
 ///
 /// # Arguments
 ///
@@ -169,53 +400,40 @@ pub fn read_sentiment_lexicon(file_path: &str) -> io::Result<HashMap<String, Sen
 ///
 /// # Returns
 ///
-/// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+/// A `Result` which is either a `SentimentReport` if successful, or an `io::Error`.
+pub fn analyze_sentiment(entries: Vec<Entry>) -> io::Result<SentimentReport> {
+    Ok(default_analyzer()?.analyze(&entries))
+}
 
-pub fn analyze_sentiment(entries: Vec<Entry>) -> io::Result<()> {
-    let entry_contents: Vec<String> = entries.into_iter().map(|e| e.content).collect();
-    
-    let stopwords_file = "files/stopwords.csv";
-    let lexicon_file = "files/SWNetTR.csv";
-    
-    let stopwords = read_stopwords(stopwords_file)?;
-    let lexicon = read_sentiment_lexicon(lexicon_file)?;
-    
-    let mut total_tone: f32 = 0.0;
-    let mut total_positive_tone: f32 = 0.0;
-    let mut total_negative_tone: f32 = 0.0;
-    let mut total_polarity: i32 = 0;
-    let mut total_positive_polarity: i32 = 0;
-    let mut total_negative_polarity: i32 = 0;
-    
-    for entry in &entry_contents {
-        let processed_entry = preprocess_text(entry, &stopwords);
-        for word in processed_entry.split_whitespace() {
-            if let Some(sentiment) = lexicon.get(word) {
-                total_tone += sentiment.tone;
-                total_polarity += sentiment.polarity;
-                if sentiment.polarity == 1 {
-                    total_positive_tone += sentiment.tone;
-                    total_positive_polarity += 1;
-                } else if sentiment.polarity == -1 {
-                    total_negative_tone += sentiment.tone;
-                    total_negative_polarity += 1;
-                }
-            }
-        }
-    }
-    
-    println!("+---------------------------------------+");
-    println!("|            Sentiment Analysis         |");
-    println!("+---------------------------------------+");
-    println!("| Total Tone:                {:>10.2}  |", total_tone);
-    println!("| Total Polarity:            {:>10}  |", total_polarity);
-    println!("+---------------------------------------+");
-    println!("| Positive Tone:             {:>10.2}  |", total_positive_tone);
-    println!("| Positive Polarity Count:   {:>10}  |", total_positive_polarity);
-    println!("+---------------------------------------+");
-    println!("| Negative Tone:             {:>10.2}  |", total_negative_tone);
-    println!("| Negative Polarity Count:   {:>10}  |", total_negative_polarity);
-    println!("+---------------------------------------+");
-    
-    Ok(())
-}
\ No newline at end of file
+/// Performs sentiment analysis like `analyze_sentiment` with an optional stemmer for lexicon lookups.
+///
+/// # Arguments
+///
+/// * `entries` - A vector of `Entry` structs representing the entries to analyze.
+/// * `stemmer` - An optional `Stemmer`; `None` only matches words exactly as they appear in the lexicon.
+///
+/// # Returns
+///
+/// A `Result` which is either a `SentimentReport` if successful, or an `io::Error`.
+pub fn analyze_sentiment_with(entries: Vec<Entry>, stemmer: Option<&Stemmer>) -> io::Result<SentimentReport> {
+    Ok(default_analyzer()?.stemmer(stemmer.cloned()).analyze(&entries))
+}
+
+/// Builds the analyzer used by `analyze_sentiment`, with its default stemmer.
+fn default_analyzer() -> io::Result<SentimentAnalyzer> {
+    #[cfg(feature = "embedded-resources")]
+    let analyzer = SentimentAnalyzer::embedded();
+    #[cfg(not(feature = "embedded-resources"))]
+    let analyzer = {
+        let stopwords_file = "files/stopwords.csv";
+        let lexicon_file = "files/SWNetTR.csv";
+        let expressions_file = "files/expressions.csv";
+        // the expression lexicon is optional; older `files/` folders do not have it
+        let expressions = match read_expression_lexicon(expressions_file) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            result => result?,
+        };
+        SentimentAnalyzer::from_files(lexicon_file, stopwords_file)?.expressions(expressions)
+    };
+    Ok(analyzer)
+}
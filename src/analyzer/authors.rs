@@ -0,0 +1,332 @@
+//! # RustySozluk Authors
+//!
+//! Per-author aggregates for the participants of a title: activity (entry counts, favorites and
+//! active hours), vocabulary (richness and the words an author uses more than the others),
+//! sentiment and a stylometric feature vector.
+//!
+//! Style vectors are compared after standardizing every feature over the analyzed authors, so
+//! that no single feature dominates the distance because of its scale.
+
+use chrono::{NaiveDateTime, Timelike};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::analyzer::keywords::{DocumentGrouping, Keyword, KeywordExtractor};
+use crate::analyzer::sentiment::{SentimentAnalyzer, SentimentReport};
+use crate::analyzer::tokenizer::{TokenKind, Tokenizer};
+use crate::parser::{bkz_references, Entry};
+
+/// Options for `AuthorProfiles::build`.
+#[derive(Debug, Clone)]
+pub struct AuthorOptions {
+    /// Number of distinctive words kept per author.
+    pub keywords: usize,
+    /// The analyzer used for the mean sentiment, `None` to skip sentiment.
+    /// Defaults to `SentimentAnalyzer::embedded()` with the `embedded-resources` feature.
+    pub sentiment: Option<SentimentAnalyzer>,
+}
+
+impl Default for AuthorOptions {
+    fn default() -> Self {
+        AuthorOptions {
+            keywords: 10,
+            #[cfg(feature = "embedded-resources")]
+            sentiment: Some(SentimentAnalyzer::embedded()),
+            #[cfg(not(feature = "embedded-resources"))]
+            sentiment: None,
+        }
+    }
+}
+
+/// Stylometric features of an author, averaged over their entries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StyleVector {
+    /// Average number of characters per word.
+    pub mean_word_length: f64,
+    /// Average number of words per sentence.
+    pub mean_sentence_length: f64,
+    /// Distinct words divided by words.
+    pub type_token_ratio: f64,
+    /// Share of the distinct words that are used only once.
+    pub hapax_ratio: f64,
+    /// Share of the words that are stopwords.
+    pub stopword_ratio: f64,
+    /// Share of the letters that are uppercase.
+    pub uppercase_ratio: f64,
+    /// Punctuation marks per word.
+    pub punctuation_rate: f64,
+    /// Exclamation marks per entry.
+    pub exclamations: f64,
+    /// Question marks per entry.
+    pub questions: f64,
+    /// Line breaks per entry.
+    pub line_breaks: f64,
+    /// Emoji per entry.
+    pub emoji: f64,
+    /// Links per entry.
+    pub urls: f64,
+    /// `(bkz: ...)` references per entry.
+    pub references: f64,
+}
+
+impl StyleVector {
+    /// Names of the features in the order of `to_vec`.
+    pub const FEATURES: [&'static str; 13] = [
+        "mean_word_length", "mean_sentence_length", "type_token_ratio", "hapax_ratio", "stopword_ratio",
+        "uppercase_ratio", "punctuation_rate", "exclamations", "questions", "line_breaks", "emoji", "urls", "references",
+    ];
+
+    /// Returns the features as a vector, in the order of `FEATURES`.
+    pub fn to_vec(&self) -> Vec<f64> {
+        vec![
+            self.mean_word_length, self.mean_sentence_length, self.type_token_ratio, self.hapax_ratio, self.stopword_ratio,
+            self.uppercase_ratio, self.punctuation_rate, self.exclamations, self.questions, self.line_breaks,
+            self.emoji, self.urls, self.references,
+        ]
+    }
+}
+
+/// Aggregates of one author.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuthorProfile {
+    pub username: String,
+    /// Number of entries.
+    pub entries: usize,
+    /// Sum of the favorite counts of the entries.
+    pub favorites: u64,
+    /// Creation date of the earliest entry, if any date could be parsed.
+    pub first_entry: Option<NaiveDateTime>,
+    /// Creation date of the latest entry, if any date could be parsed.
+    pub last_entry: Option<NaiveDateTime>,
+    /// Number of entries created in each hour of the day (index `0` is 00:00-00:59).
+    pub active_hours: [usize; 24],
+    /// Average entry length in characters.
+    pub mean_length: f64,
+    /// Average number of words per entry.
+    pub mean_words: f64,
+    /// Number of distinct words.
+    pub vocabulary: usize,
+    /// Words the author uses more than the other authors, by TF-IDF with authors as documents.
+    pub distinctive_words: Vec<Keyword>,
+    /// Average entry tone, `None` if sentiment was skipped.
+    pub mean_tone: Option<f32>,
+    /// Stylometric features.
+    pub style: StyleVector,
+}
+
+impl AuthorProfile {
+    /// Returns the hour of the day with the most entries, `None` if no entry has a date.
+    pub fn most_active_hour(&self) -> Option<usize> {
+        let (hour, &count) = self.active_hours.iter().enumerate().max_by_key(|&(hour, count)| (count, std::cmp::Reverse(hour)))?;
+        (count > 0).then_some(hour)
+    }
+}
+
+/// Profiles of all authors of a list of entries.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::authors::{AuthorOptions, AuthorProfiles};
+/// use rustysozluk::Entry;
+///
+/// let entry = |username: &str, content: &str| Entry { username: username.to_string(), content: content.to_string(), ..Default::default() };
+/// let entries = vec![entry("ali", "Bence çok iyi!"), entry("ali", "Katılıyorum."), entry("veli", "bilmiyorum ki")];
+/// let profiles = AuthorProfiles::build(&entries, &HashSet::new(), &AuthorOptions { sentiment: None, ..Default::default() });
+/// assert_eq!(profiles.authors[0].username, "ali");
+/// assert_eq!(profiles.authors[0].entries, 2);
+/// assert!(profiles.style_distance("ali", "veli").is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AuthorProfiles {
+    /// The profiles, sorted by descending entry count and then by username.
+    pub authors: Vec<AuthorProfile>,
+}
+
+impl AuthorProfiles {
+    /// Builds the profiles of every author of the entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to analyze.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    /// * `options` - The `AuthorOptions` to apply.
+    ///
+    /// # Returns
+    ///
+    /// An `AuthorProfiles` with one profile per username.
+    pub fn build(entries: &[Entry], stopwords: &HashSet<String>, options: &AuthorOptions) -> Self {
+        let mut by_author: HashMap<&str, Vec<&Entry>> = HashMap::new();
+        for entry in entries {
+            by_author.entry(entry.username.as_str()).or_default().push(entry);
+        }
+        let mut keywords: HashMap<String, Vec<Keyword>> = KeywordExtractor::new()
+            .grouping(DocumentGrouping::User)
+            .max_keywords(options.keywords)
+            .extract(entries, stopwords)
+            .into_iter()
+            .map(|document| (document.document, document.keywords))
+            .collect();
+
+        let mut authors: Vec<AuthorProfile> = by_author
+            .into_iter()
+            .map(|(username, entries)| {
+                let mut profile = profile(username, &entries, stopwords);
+                profile.distinctive_words = keywords.remove(username).unwrap_or_default();
+                profile.mean_tone = options.sentiment.as_ref().map(|analyzer| {
+                    SentimentReport::from_entries(entries.iter().map(|entry| analyzer.score_text(&entry.content)).collect()).mean_entry_tone
+                });
+                profile
+            })
+            .collect();
+        authors.sort_by(|a, b| b.entries.cmp(&a.entries).then_with(|| a.username.cmp(&b.username)));
+        AuthorProfiles { authors }
+    }
+
+    /// Returns the profile of an author.
+    pub fn author(&self, username: &str) -> Option<&AuthorProfile> {
+        self.authors.iter().find(|profile| profile.username == username)
+    }
+
+    /// Returns the style vectors of all authors with every feature standardized to zero mean
+    /// and unit variance over the authors, in the order of `authors`.
+    pub fn standardized_styles(&self) -> Vec<Vec<f64>> {
+        let vectors: Vec<Vec<f64>> = self.authors.iter().map(|profile| profile.style.to_vec()).collect();
+        let count = vectors.len().max(1) as f64;
+        let mut standardized = vectors.clone();
+        for feature in 0..StyleVector::FEATURES.len() {
+            let mean = vectors.iter().map(|v| v[feature]).sum::<f64>() / count;
+            let deviation = (vectors.iter().map(|v| (v[feature] - mean).powi(2)).sum::<f64>() / count).sqrt();
+            for vector in &mut standardized {
+                vector[feature] = if deviation > 0.0 { (vector[feature] - mean) / deviation } else { 0.0 };
+            }
+        }
+        standardized
+    }
+
+    /// Returns the Euclidean distance between the standardized style vectors of two authors;
+    /// smaller means more similar writing. `None` if either author is unknown.
+    pub fn style_distance(&self, a: &str, b: &str) -> Option<f64> {
+        let index = |username: &str| self.authors.iter().position(|profile| profile.username == username);
+        let (a, b) = (index(a)?, index(b)?);
+        let styles = self.standardized_styles();
+        Some(styles[a].iter().zip(&styles[b]).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt())
+    }
+
+    /// Returns the authors with the most similar style to the given one, closest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The author to compare with.
+    /// * `k` - The number of authors to return.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<(String, f64)>` of usernames and style distances, empty if the author is unknown.
+    pub fn most_similar(&self, username: &str, k: usize) -> Vec<(String, f64)> {
+        let Some(index) = self.authors.iter().position(|profile| profile.username == username) else { return Vec::new() };
+        let styles = self.standardized_styles();
+        let mut distances: Vec<(String, f64)> = self
+            .authors
+            .iter()
+            .enumerate()
+            .filter(|&(other, _)| other != index)
+            .map(|(other, profile)| {
+                let distance = styles[index].iter().zip(&styles[other]).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt();
+                (profile.username.clone(), distance)
+            })
+            .collect();
+        distances.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        distances.truncate(k);
+        distances
+    }
+}
+
+/// Computes everything but the keywords and sentiment of an author.
+fn profile(username: &str, entries: &[&Entry], stopwords: &HashSet<String>) -> AuthorProfile {
+    let tokenizer = Tokenizer::new();
+    let mut active_hours = [0; 24];
+    let mut word_counts: HashMap<String, usize> = HashMap::new();
+    let (mut words, mut word_chars, mut stopword_count, mut sentences) = (0usize, 0usize, 0usize, 0usize);
+    let (mut letters, mut uppercase, mut punctuation, mut characters) = (0usize, 0usize, 0usize, 0usize);
+    let (mut exclamations, mut questions, mut line_breaks, mut emoji, mut urls, mut references) = (0, 0, 0, 0, 0, 0);
+    let mut dates = Vec::new();
+
+    for entry in entries {
+        if let Some(created) = entry.created_at() {
+            active_hours[created.hour() as usize] += 1;
+            dates.push(created);
+        }
+        let content = &entry.content;
+        characters += content.chars().count();
+        for c in content.chars() {
+            if c.is_alphabetic() {
+                letters += 1;
+                uppercase += usize::from(c.is_uppercase());
+            } else if is_punctuation(c) {
+                punctuation += 1;
+            }
+            exclamations += usize::from(c == '!');
+            questions += usize::from(c == '?');
+            line_breaks += usize::from(c == '\n');
+        }
+        sentences += content
+            .split(['.', '!', '?', '\n'])
+            .filter(|sentence| sentence.chars().any(char::is_alphanumeric))
+            .count();
+        references += bkz_references(content).len();
+        for token in tokenizer.tokenize(content) {
+            match token.kind {
+                TokenKind::Word => {
+                    words += 1;
+                    word_chars += token.text.chars().count();
+                    stopword_count += usize::from(stopwords.contains(&token.text));
+                    *word_counts.entry(token.text).or_insert(0) += 1;
+                }
+                TokenKind::Emoji => emoji += 1,
+                TokenKind::Url => {
+                    // the dots and slashes of a link are not punctuation of the text
+                    urls += 1;
+                    punctuation -= token.raw.chars().filter(|&c| is_punctuation(c)).count();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let ratio = |part: usize, whole: usize| if whole == 0 { 0.0 } else { part as f64 / whole as f64 };
+    let per_entry = |count: usize| ratio(count, entries.len());
+    let hapaxes = word_counts.values().filter(|&&count| count == 1).count();
+    AuthorProfile {
+        username: username.to_string(),
+        entries: entries.len(),
+        favorites: entries.iter().map(|entry| entry.favorites as u64).sum(),
+        first_entry: dates.iter().min().copied(),
+        last_entry: dates.iter().max().copied(),
+        active_hours,
+        mean_length: per_entry(characters),
+        mean_words: per_entry(words),
+        vocabulary: word_counts.len(),
+        distinctive_words: Vec::new(),
+        mean_tone: None,
+        style: StyleVector {
+            mean_word_length: ratio(word_chars, words),
+            mean_sentence_length: ratio(words, sentences),
+            type_token_ratio: ratio(word_counts.len(), words),
+            hapax_ratio: ratio(hapaxes, word_counts.len()),
+            stopword_ratio: ratio(stopword_count, words),
+            uppercase_ratio: ratio(uppercase, letters),
+            punctuation_rate: ratio(punctuation, words),
+            exclamations: per_entry(exclamations),
+            questions: per_entry(questions),
+            line_breaks: per_entry(line_breaks),
+            emoji: per_entry(emoji),
+            urls: per_entry(urls),
+            references: per_entry(references),
+        },
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || matches!(c, '…' | '“' | '”' | '‘' | '’')
+}
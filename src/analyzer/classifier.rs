@@ -0,0 +1,630 @@
+//! # RustySozluk Classifier
+//!
+//! Supervised text classification for hand-labeled entries, e.g. positive/negative/neutral or
+//! spam/not-spam. Two models are available: multinomial naive Bayes, which trains in one pass and
+//! works well with little data, and multinomial logistic regression, which is usually more
+//! accurate once a few hundred examples per label are available.
+//!
+//! Features are the tokens of `preprocess_tokens`, optionally stemmed and extended with word
+//! bigrams. Trained models are plain JSON files, so they can be trained once and shipped with an
+//! application.
+
+use csv::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::sync::OnceLock;
+use crate::analyzer::analyzer::preprocess_tokens;
+use crate::analyzer::rng::Rng;
+use crate::analyzer::stemmer::Stemmer;
+use crate::parser::Entry;
+
+/// A text with its label, the unit of training data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabeledText {
+    pub content: String,
+    pub label: String,
+}
+
+impl LabeledText {
+    /// Creates a labeled text.
+    pub fn new(content: impl Into<String>, label: impl Into<String>) -> Self {
+        LabeledText { content: content.into(), label: label.into() }
+    }
+}
+
+/// Reads labeled texts from a CSV file with `content` and `label` columns.
+///
+/// Other columns are ignored, so a CSV created by `export_to_csv` can be labeled by adding a
+/// `label` column.
+///
+/// # Arguments
+///
+/// * `file_path` - A string slice containing the path to the CSV file.
+///
+/// # Returns
+///
+/// A `Vec<LabeledText>` in file order.
+pub fn read_labeled_csv(file_path: &str) -> io::Result<Vec<LabeledText>> {
+    read_labeled_csv_from(File::open(file_path)?)
+}
+
+/// Reads labeled texts from any reader, in the same format as `read_labeled_csv`.
+///
+/// # Arguments
+///
+/// * `reader` - The source implementing `Read`.
+///
+/// # Returns
+///
+/// A `Vec<LabeledText>` in reading order. Rows with an empty label are skipped.
+pub fn read_labeled_csv_from<R: Read>(reader: R) -> io::Result<Vec<LabeledText>> {
+    let mut rdr = Reader::from_reader(reader);
+    let mut texts = Vec::new();
+    for result in rdr.deserialize() {
+        let text: LabeledText = result?;
+        if !text.label.trim().is_empty() {
+            texts.push(LabeledText { label: text.label.trim().to_string(), ..text });
+        }
+    }
+    Ok(texts)
+}
+
+/// The model trained by a `ClassifierTrainer`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Algorithm {
+    /// Multinomial naive Bayes with additive smoothing.
+    #[default]
+    NaiveBayes,
+    /// Multinomial logistic regression trained with stochastic gradient descent.
+    LogisticRegression,
+}
+
+/// Turns texts into feature counts; stored in the model so that classification uses the same
+/// features as training.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Features {
+    stopwords: HashSet<String>,
+    stemmer: Option<Stemmer>,
+    bigrams: bool,
+}
+
+impl Features {
+    fn count(&self, text: &str) -> HashMap<String, usize> {
+        let tokens: Vec<String> = preprocess_tokens(text, &self.stopwords)
+            .into_iter()
+            .map(|token| match &self.stemmer {
+                Some(stemmer) => stemmer.stem(&token),
+                None => token,
+            })
+            .collect();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for token in &tokens {
+            *counts.entry(token.clone()).or_insert(0) += 1;
+        }
+        if self.bigrams {
+            for pair in tokens.windows(2) {
+                *counts.entry(format!("{} {}", pair[0], pair[1])).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Trains classifiers from labeled texts.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::classifier::{Algorithm, ClassifierTrainer, LabeledText};
+///
+/// let examples = vec![
+///     LabeledText::new("harika bir film bayıldım", "pozitif"),
+///     LabeledText::new("muhteşem oyunculuk harika", "pozitif"),
+///     LabeledText::new("berbat bir film sıkıldım", "negatif"),
+///     LabeledText::new("rezalet senaryo berbat", "negatif"),
+/// ];
+/// let trainer = ClassifierTrainer::new().algorithm(Algorithm::NaiveBayes);
+/// let classifier = trainer.train(&examples, &HashSet::new());
+/// assert_eq!(classifier.classify_text("harika oyunculuk").label, "pozitif");
+/// assert_eq!(classifier.classify_text("berbat senaryo").label, "negatif");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClassifierTrainer {
+    algorithm: Algorithm,
+    stemmer: Option<Stemmer>,
+    bigrams: bool,
+    min_frequency: usize,
+    smoothing: f64,
+    epochs: usize,
+    learning_rate: f64,
+    regularization: f64,
+    seed: u64,
+}
+
+impl Default for ClassifierTrainer {
+    fn default() -> Self {
+        ClassifierTrainer::new()
+    }
+}
+
+impl ClassifierTrainer {
+    /// Creates a naive Bayes trainer over unigrams with add-one smoothing. Logistic regression
+    /// defaults to 30 epochs with a learning rate of 0.5 and L2 regularization of 0.0001.
+    pub fn new() -> Self {
+        ClassifierTrainer {
+            algorithm: Algorithm::default(),
+            stemmer: None,
+            bigrams: false,
+            min_frequency: 1,
+            smoothing: 1.0,
+            epochs: 30,
+            learning_rate: 0.5,
+            regularization: 1e-4,
+            seed: 42,
+        }
+    }
+
+    /// Sets the model to train.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the stemmer applied to every token; `None` (the default) keeps words as written.
+    pub fn stemmer(mut self, stemmer: Option<Stemmer>) -> Self {
+        self.stemmer = stemmer;
+        self
+    }
+
+    /// Adds pairs of consecutive tokens as features, which captures phrases such as "hiç beğenmedim".
+    pub fn bigrams(mut self, bigrams: bool) -> Self {
+        self.bigrams = bigrams;
+        self
+    }
+
+    /// Sets how often a feature has to occur in the training texts to be used.
+    pub fn min_frequency(mut self, min_frequency: usize) -> Self {
+        self.min_frequency = min_frequency;
+        self
+    }
+
+    /// Sets the additive smoothing of naive Bayes.
+    pub fn smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Sets the number of passes of logistic regression over the training texts.
+    pub fn epochs(mut self, epochs: usize) -> Self {
+        self.epochs = epochs;
+        self
+    }
+
+    /// Sets the initial learning rate of logistic regression; it decays with every epoch.
+    pub fn learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Sets the L2 regularization strength of logistic regression.
+    pub fn regularization(mut self, regularization: f64) -> Self {
+        self.regularization = regularization;
+        self
+    }
+
+    /// Sets the seed used to shuffle texts for logistic regression and cross-validation.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Trains a classifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `examples` - The labeled training texts.
+    /// * `stopwords` - A reference to a HashSet containing stopwords; it is stored in the model.
+    ///
+    /// # Returns
+    ///
+    /// A trained `Classifier`. Without examples it has no labels and classifies everything as "".
+    pub fn train(&self, examples: &[LabeledText], stopwords: &HashSet<String>) -> Classifier {
+        let features = Features {
+            stopwords: stopwords.clone(),
+            stemmer: self.stemmer.clone(),
+            bigrams: self.bigrams,
+        };
+        let counts: Vec<HashMap<String, usize>> = examples.iter().map(|example| features.count(&example.content)).collect();
+
+        let mut labels: Vec<String> = examples.iter().map(|example| example.label.clone()).collect();
+        labels.sort();
+        labels.dedup();
+        let targets: Vec<usize> = examples.iter().map(|example| labels.binary_search(&example.label).unwrap()).collect();
+
+        let mut totals: HashMap<&str, usize> = HashMap::new();
+        for document in &counts {
+            for (feature, count) in document {
+                *totals.entry(feature).or_insert(0) += count;
+            }
+        }
+        let mut vocabulary: Vec<String> = totals
+            .into_iter()
+            .filter(|&(_, total)| total >= self.min_frequency)
+            .map(|(feature, _)| feature.to_string())
+            .collect();
+        vocabulary.sort();
+        let index: HashMap<String, usize> = vocabulary.iter().enumerate().map(|(id, feature)| (feature.clone(), id)).collect();
+        let documents: Vec<Vec<(usize, f64)>> = counts
+            .iter()
+            .map(|document| {
+                let mut terms: Vec<(usize, f64)> = document.iter().filter_map(|(feature, &count)| Some((*index.get(feature)?, count as f64))).collect();
+                terms.sort_by_key(|&(term, _)| term);
+                terms
+            })
+            .collect();
+
+        let model = match self.algorithm {
+            Algorithm::NaiveBayes => self.naive_bayes(&documents, &targets, labels.len(), vocabulary.len()),
+            Algorithm::LogisticRegression => self.logistic_regression(&documents, &targets, labels.len(), vocabulary.len()),
+        };
+        let classifier = Classifier { labels, vocabulary, features, model, index: OnceLock::new() };
+        let _ = classifier.index.set(index);
+        classifier
+    }
+
+    /// Estimates how well the trainer generalizes with stratified k-fold cross-validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `examples` - The labeled texts.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    /// * `folds` - The number of folds, at least 2.
+    ///
+    /// # Returns
+    ///
+    /// A `CrossValidation` with accuracy, per-label metrics and the confusion matrix.
+    pub fn cross_validate(&self, examples: &[LabeledText], stopwords: &HashSet<String>, folds: usize) -> CrossValidation {
+        let folds = folds.max(2);
+        let mut labels: Vec<String> = examples.iter().map(|example| example.label.clone()).collect();
+        labels.sort();
+        labels.dedup();
+
+        // Shuffle, then deal the examples of every label round-robin so each fold keeps the label mix.
+        let mut order: Vec<usize> = (0..examples.len()).collect();
+        let mut rng = Rng::new(self.seed);
+        for i in (1..order.len()).rev() {
+            order.swap(i, rng.below(i + 1));
+        }
+        order.sort_by(|&a, &b| examples[a].label.cmp(&examples[b].label));
+        let mut fold_of = vec![0; examples.len()];
+        for (position, &example) in order.iter().enumerate() {
+            fold_of[example] = position % folds;
+        }
+
+        let mut confusion = vec![vec![0; labels.len()]; labels.len()];
+        for fold in 0..folds {
+            let training: Vec<LabeledText> = (0..examples.len()).filter(|&i| fold_of[i] != fold).map(|i| examples[i].clone()).collect();
+            let classifier = self.train(&training, stopwords);
+            for example in (0..examples.len()).filter(|&i| fold_of[i] == fold).map(|i| &examples[i]) {
+                let predicted = classifier.classify_text(&example.content).label;
+                let actual = labels.binary_search(&example.label).unwrap();
+                if let Ok(predicted) = labels.binary_search(&predicted) {
+                    confusion[actual][predicted] += 1;
+                }
+            }
+        }
+        CrossValidation::from_confusion(folds, labels, confusion)
+    }
+
+    fn naive_bayes(&self, documents: &[Vec<(usize, f64)>], targets: &[usize], labels: usize, features: usize) -> Model {
+        let mut documents_per_label = vec![0.0; labels];
+        let mut counts = vec![vec![0.0; features]; labels];
+        for (document, &target) in documents.iter().zip(targets) {
+            documents_per_label[target] += 1.0;
+            for &(term, count) in document {
+                counts[target][term] += count;
+            }
+        }
+        let priors = documents_per_label.iter().map(|count| (count / documents.len() as f64).ln()).collect();
+        let likelihoods = counts
+            .into_iter()
+            .map(|label_counts| {
+                let denominator = label_counts.iter().sum::<f64>() + self.smoothing * features as f64;
+                label_counts.into_iter().map(|count| ((count + self.smoothing) / denominator).ln()).collect()
+            })
+            .collect();
+        Model::NaiveBayes { priors, likelihoods }
+    }
+
+    fn logistic_regression(&self, documents: &[Vec<(usize, f64)>], targets: &[usize], labels: usize, features: usize) -> Model {
+        let inputs: Vec<Vec<(usize, f64)>> = documents.iter().map(|document| normalized(document)).collect();
+        let mut weights = vec![vec![0.0; features]; labels];
+        let mut bias = vec![0.0; labels];
+        let mut order: Vec<usize> = (0..documents.len()).collect();
+        let mut rng = Rng::new(self.seed);
+        let mut probabilities = vec![0.0; labels];
+        for epoch in 0..self.epochs {
+            for i in (1..order.len()).rev() {
+                order.swap(i, rng.below(i + 1));
+            }
+            let rate = self.learning_rate / (1.0 + epoch as f64).sqrt();
+            for &document in &order {
+                let input = &inputs[document];
+                for (label, probability) in probabilities.iter_mut().enumerate() {
+                    *probability = bias[label] + input.iter().map(|&(term, value)| weights[label][term] * value).sum::<f64>();
+                }
+                softmax(&mut probabilities);
+                for label in 0..labels {
+                    let gradient = probabilities[label] - if label == targets[document] { 1.0 } else { 0.0 };
+                    bias[label] -= rate * gradient;
+                    for &(term, value) in input {
+                        let weight = &mut weights[label][term];
+                        *weight -= rate * (gradient * value + self.regularization * *weight);
+                    }
+                }
+            }
+        }
+        Model::LogisticRegression { weights, bias }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Model {
+    NaiveBayes { priors: Vec<f64>, likelihoods: Vec<Vec<f64>> },
+    LogisticRegression { weights: Vec<Vec<f64>>, bias: Vec<f64> },
+}
+
+/// The result of classifying a text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Prediction {
+    /// The most probable label.
+    pub label: String,
+    /// The probability of `label`, between 0 and 1.
+    pub confidence: f64,
+    /// The probability of every label, most probable first.
+    pub probabilities: Vec<(String, f64)>,
+}
+
+/// A trained text classifier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Classifier {
+    labels: Vec<String>,
+    vocabulary: Vec<String>,
+    features: Features,
+    model: Model,
+    #[serde(skip)]
+    index: OnceLock<HashMap<String, usize>>,
+}
+
+impl Classifier {
+    /// Returns the labels the classifier can predict, in alphabetical order.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Returns the algorithm the classifier was trained with.
+    pub fn algorithm(&self) -> Algorithm {
+        match self.model {
+            Model::NaiveBayes { .. } => Algorithm::NaiveBayes,
+            Model::LogisticRegression { .. } => Algorithm::LogisticRegression,
+        }
+    }
+
+    /// Classifies the content of an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The entry to classify.
+    ///
+    /// # Returns
+    ///
+    /// A `Prediction` with the most probable label and the probabilities of all labels.
+    pub fn classify(&self, entry: &Entry) -> Prediction {
+        self.classify_text(&entry.content)
+    }
+
+    /// Classifies a text like `classify`.
+    pub fn classify_text(&self, text: &str) -> Prediction {
+        let index = self.index.get_or_init(|| self.vocabulary.iter().enumerate().map(|(id, feature)| (feature.clone(), id)).collect());
+        let mut input: Vec<(usize, f64)> = self
+            .features
+            .count(text)
+            .into_iter()
+            .filter_map(|(feature, count)| Some((*index.get(&feature)?, count as f64)))
+            .collect();
+        input.sort_by_key(|&(term, _)| term);
+
+        let mut scores: Vec<f64> = match &self.model {
+            Model::NaiveBayes { priors, likelihoods } => priors
+                .iter()
+                .zip(likelihoods)
+                .map(|(prior, likelihood)| prior + input.iter().map(|&(term, count)| count * likelihood[term]).sum::<f64>())
+                .collect(),
+            Model::LogisticRegression { weights, bias } => {
+                let input = normalized(&input);
+                bias.iter()
+                    .zip(weights)
+                    .map(|(bias, weights)| bias + input.iter().map(|&(term, value)| weights[term] * value).sum::<f64>())
+                    .collect()
+            }
+        };
+        softmax(&mut scores);
+
+        let mut probabilities: Vec<(String, f64)> = self.labels.iter().cloned().zip(scores).collect();
+        probabilities.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let (label, confidence) = probabilities.first().cloned().unwrap_or_default();
+        Prediction { label, confidence, probabilities }
+    }
+
+    /// Writes the classifier as JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination implementing `Write`.
+    pub fn write_json<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Saves the classifier to a JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - A `&str` specifying the path of the file to create.
+    pub fn export_to_json(&self, file_name: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(file_name)?);
+        self.write_json(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a classifier written by `write_json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source implementing `Read`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either the `Classifier`, or an `io::Error` of kind `InvalidData` if the
+    /// JSON is malformed or the model weights do not match its labels and vocabulary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    /// use std::io::ErrorKind;
+    /// use rustysozluk::analyzer::classifier::{Classifier, ClassifierTrainer, LabeledText};
+    ///
+    /// let examples = vec![LabeledText::new("harika film", "pozitif"), LabeledText::new("berbat film", "negatif")];
+    /// let classifier = ClassifierTrainer::new().train(&examples, &HashSet::new());
+    /// let mut json = Vec::new();
+    /// classifier.write_json(&mut json).unwrap();
+    /// assert!(Classifier::read_json(json.as_slice()).is_ok());
+    ///
+    /// // a hand-edited model with an extra label is rejected
+    /// let edited = String::from_utf8(json).unwrap().replace(r#""labels":["#, r#""labels":["nötr","#);
+    /// let error = Classifier::read_json(edited.as_bytes()).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidData);
+    /// ```
+    pub fn read_json<R: Read>(reader: R) -> io::Result<Classifier> {
+        let classifier: Classifier = serde_json::from_reader(reader)?;
+        classifier.validate()?;
+        Ok(classifier)
+    }
+
+    /// Checks that the model has one row of weights per label and one weight per vocabulary term.
+    fn validate(&self) -> io::Result<()> {
+        let (per_label, rows): (&[f64], &[Vec<f64>]) = match &self.model {
+            Model::NaiveBayes { priors, likelihoods } => (priors, likelihoods),
+            Model::LogisticRegression { weights, bias } => (bias, weights),
+        };
+        let valid = per_label.len() == self.labels.len()
+            && rows.len() == self.labels.len()
+            && rows.iter().all(|row| row.len() == self.vocabulary.len());
+        if valid {
+            Ok(())
+        } else {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "classifier model does not match its labels and vocabulary"))
+        }
+    }
+
+    /// Loads a classifier saved by `export_to_json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - A `&str` specifying the path of the JSON file.
+    pub fn import_from_json(file_name: &str) -> io::Result<Classifier> {
+        Classifier::read_json(BufReader::new(File::open(file_name)?))
+    }
+}
+
+/// Precision, recall and F1 of one label.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LabelMetrics {
+    pub label: String,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    /// Number of examples with this label.
+    pub support: usize,
+}
+
+/// Cross-validation results of a `ClassifierTrainer`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrossValidation {
+    /// The number of folds.
+    pub folds: usize,
+    /// Share of correctly classified examples.
+    pub accuracy: f64,
+    /// Unweighted mean of the F1 scores of the labels.
+    pub macro_f1: f64,
+    /// Metrics of every label, in alphabetical order.
+    pub labels: Vec<LabelMetrics>,
+    /// `confusion[actual][predicted]` counts, indexed like `labels`.
+    pub confusion: Vec<Vec<usize>>,
+}
+
+impl CrossValidation {
+    fn from_confusion(folds: usize, labels: Vec<String>, confusion: Vec<Vec<usize>>) -> Self {
+        let total: usize = confusion.iter().flatten().sum();
+        let correct: usize = (0..labels.len()).map(|label| confusion[label][label]).sum();
+        let ratio = |a: usize, b: usize| if b == 0 { 0.0 } else { a as f64 / b as f64 };
+        let metrics: Vec<LabelMetrics> = labels
+            .into_iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let support: usize = confusion[i].iter().sum();
+                let predicted: usize = confusion.iter().map(|row| row[i]).sum();
+                let (precision, recall) = (ratio(confusion[i][i], predicted), ratio(confusion[i][i], support));
+                let f1 = if precision + recall == 0.0 { 0.0 } else { 2.0 * precision * recall / (precision + recall) };
+                LabelMetrics { label, precision, recall, f1, support }
+            })
+            .collect();
+        CrossValidation {
+            folds,
+            accuracy: ratio(correct, total),
+            macro_f1: if metrics.is_empty() { 0.0 } else { metrics.iter().map(|metric| metric.f1).sum::<f64>() / metrics.len() as f64 },
+            labels: metrics,
+            confusion,
+        }
+    }
+}
+
+impl fmt::Display for CrossValidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}-fold cross-validation: accuracy {:.3}, macro F1 {:.3}", self.folds, self.accuracy, self.macro_f1)?;
+        let width = self.labels.iter().map(|metric| metric.label.chars().count()).max().unwrap_or(0).max(5);
+        writeln!(f, "{:<width$}  {:>9}  {:>6}  {:>6}  {:>7}", "label", "precision", "recall", "f1", "support")?;
+        for metric in &self.labels {
+            writeln!(f, "{:<width$}  {:>9.3}  {:>6.3}  {:>6.3}  {:>7}", metric.label, metric.precision, metric.recall, metric.f1, metric.support)?;
+        }
+        Ok(())
+    }
+}
+
+/// Scales log-counts of a sparse document to unit length.
+fn normalized(document: &[(usize, f64)]) -> Vec<(usize, f64)> {
+    let logged: Vec<(usize, f64)> = document.iter().map(|&(term, count)| (term, (1.0 + count).ln())).collect();
+    let norm = logged.iter().map(|(_, value)| value * value).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return logged;
+    }
+    logged.into_iter().map(|(term, value)| (term, value / norm)).collect()
+}
+
+/// Turns scores into probabilities in place.
+fn softmax(scores: &mut [f64]) {
+    let max = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let mut sum = 0.0;
+    for score in scores.iter_mut() {
+        *score = (*score - max).exp();
+        sum += *score;
+    }
+    for score in scores.iter_mut() {
+        *score /= sum;
+    }
+}
@@ -0,0 +1,247 @@
+//! # RustySozluk Duplicate Detection
+//!
+//! Finds near-duplicate entries such as copypasta and spam posted under several titles.
+//!
+//! Entries are normalized with `preprocess_text` and cut into overlapping character shingles.
+//! Every entry gets a MinHash signature whose components agree between two entries with a
+//! probability equal to the Jaccard similarity of their shingle sets. Locality-sensitive hashing
+//! over bands of the signatures finds candidate pairs without comparing every pair of entries,
+//! so the detector scales to hundreds of thousands of entries.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::analyzer::analyzer::preprocess_text;
+use crate::analyzer::rng::Rng;
+use crate::parser::Entry;
+use crate::util::fnv1a;
+
+/// Buckets with more entries than this are compared against their first entry only, which keeps
+/// masses of identical copies linear instead of quadratic.
+const MAX_PAIRWISE_BUCKET: usize = 50;
+
+/// A group of near-duplicate entries.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateCluster {
+    /// Positions of the entries in the analyzed slice, in ascending order.
+    pub members: Vec<usize>,
+    /// Average estimated similarity of the linked pairs of the cluster.
+    pub mean_similarity: f64,
+    /// Lowest estimated similarity of the linked pairs of the cluster.
+    pub min_similarity: f64,
+}
+
+impl DuplicateCluster {
+    /// Returns the entries of the cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The slice the cluster was found in.
+    pub fn entries<'a>(&self, entries: &'a [Entry]) -> Vec<&'a Entry> {
+        self.members.iter().filter_map(|&index| entries.get(index)).collect()
+    }
+}
+
+/// Finds near-duplicate entries with MinHash and locality-sensitive hashing.
+///
+/// With `hashes` hash functions split into `bands` bands, two entries become candidates when all
+/// hashes of at least one band agree, which happens mostly for pairs with a similarity above
+/// roughly `(1 / bands) ^ (bands / hashes)`. Candidates are kept when their estimated similarity
+/// reaches `threshold`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::dedup::DuplicateDetector;
+///
+/// let texts = [
+///     "bu başlığa gelen herkese bedava hediye var hemen tıklayın",
+///     "rust ile yazılmış güzel bir araç",
+///     "bu başlığa gelen herkese bedava hediye var, hemen tıklayın!!",
+/// ];
+/// let clusters = DuplicateDetector::new().find_texts(texts, &HashSet::new());
+/// assert_eq!(clusters[0].members, vec![0, 2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DuplicateDetector {
+    shingle_size: usize,
+    hashes: usize,
+    bands: usize,
+    threshold: f64,
+    seed: u64,
+}
+
+impl Default for DuplicateDetector {
+    fn default() -> Self {
+        DuplicateDetector::new()
+    }
+}
+
+impl DuplicateDetector {
+    /// Creates a detector with 5-character shingles, 64 hashes in 8 bands and a similarity
+    /// threshold of 0.8.
+    pub fn new() -> Self {
+        DuplicateDetector {
+            shingle_size: 5,
+            hashes: 64,
+            bands: 8,
+            threshold: 0.8,
+            seed: 0x5EED,
+        }
+    }
+
+    /// Sets the length of the character shingles.
+    pub fn shingle_size(mut self, shingle_size: usize) -> Self {
+        self.shingle_size = shingle_size.max(1);
+        self
+    }
+
+    /// Sets the number of MinHash functions and the number of LSH bands they are split into.
+    /// More bands find less similar candidates; `hashes` is rounded down to a multiple of `bands`.
+    pub fn hashes(mut self, hashes: usize, bands: usize) -> Self {
+        self.bands = bands.max(1);
+        self.hashes = (hashes / self.bands).max(1) * self.bands;
+        self
+    }
+
+    /// Sets the lowest estimated Jaccard similarity, between 0 and 1, for two entries to be linked.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the seed of the hash functions. Results are reproducible for the same seed.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Finds clusters of near-duplicate entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to analyze.
+    /// * `stopwords` - A reference to a HashSet containing stopwords removed during normalization.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<DuplicateCluster>` sorted by descending size and then by first member.
+    pub fn find(&self, entries: &[Entry], stopwords: &HashSet<String>) -> Vec<DuplicateCluster> {
+        self.find_texts(entries.iter().map(|entry| entry.content.as_str()), stopwords)
+    }
+
+    /// Finds clusters of near-duplicate texts like `find`.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - The texts to analyze.
+    /// * `stopwords` - A reference to a HashSet containing stopwords removed during normalization.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<DuplicateCluster>` whose members are positions in `texts`.
+    pub fn find_texts<I, S>(&self, texts: I, stopwords: &HashSet<String>) -> Vec<DuplicateCluster>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut rng = Rng::new(self.seed);
+        let coefficients: Vec<(u64, u64)> = (0..self.hashes).map(|_| (rng.next_u64() | 1, rng.next_u64())).collect();
+        let signatures: Vec<Option<Vec<u64>>> = texts
+            .into_iter()
+            .map(|text| self.signature(&preprocess_text(text.as_ref(), stopwords), &coefficients))
+            .collect();
+
+        let rows = self.hashes / self.bands;
+        let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+        for (index, signature) in signatures.iter().enumerate() {
+            let Some(signature) = signature else { continue };
+            for (band, values) in signature.chunks(rows).enumerate() {
+                buckets.entry((band, fnv1a(values.iter().flat_map(|value| value.to_le_bytes())))).or_default().push(index);
+            }
+        }
+
+        let similarity = |a: usize, b: usize| match (&signatures[a], &signatures[b]) {
+            (Some(a), Some(b)) => a.iter().zip(b).filter(|(x, y)| x == y).count() as f64 / a.len() as f64,
+            _ => 0.0,
+        };
+        let mut links: HashMap<(usize, usize), f64> = HashMap::new();
+        let mut checked: HashSet<(usize, usize)> = HashSet::new();
+        for members in buckets.values().filter(|members| members.len() > 1) {
+            let pairs: Vec<(usize, usize)> = if members.len() <= MAX_PAIRWISE_BUCKET {
+                members.iter().enumerate().flat_map(|(i, &a)| members[i + 1..].iter().map(move |&b| (a, b))).collect()
+            } else {
+                members[1..].iter().map(|&b| (members[0], b)).collect()
+            };
+            for pair in pairs {
+                if !checked.insert(pair) {
+                    continue;
+                }
+                let score = similarity(pair.0, pair.1);
+                if score >= self.threshold {
+                    links.insert(pair, score);
+                }
+            }
+        }
+        clusters(signatures.len(), &links)
+    }
+
+    /// Returns the MinHash signature of a normalized text, `None` if the text is empty.
+    fn signature(&self, text: &str, coefficients: &[(u64, u64)]) -> Option<Vec<u64>> {
+        let chars: Vec<char> = text.chars().collect();
+        if chars.is_empty() {
+            return None;
+        }
+        let width = self.shingle_size.min(chars.len());
+        let shingles: HashSet<u64> = chars
+            .windows(width)
+            .map(|shingle| fnv1a(shingle.iter().collect::<String>().bytes()))
+            .collect();
+        Some(
+            coefficients
+                .iter()
+                .map(|&(a, b)| shingles.iter().map(|&shingle| a.wrapping_mul(shingle).wrapping_add(b).rotate_left(32)).min().unwrap_or(u64::MAX))
+                .collect(),
+        )
+    }
+}
+
+/// Groups linked items with union-find and summarizes the similarities of each group.
+fn clusters(count: usize, links: &HashMap<(usize, usize), f64>) -> Vec<DuplicateCluster> {
+    let mut parent: Vec<usize> = (0..count).collect();
+    fn root(parent: &mut [usize], mut node: usize) -> usize {
+        while parent[node] != node {
+            parent[node] = parent[parent[node]];
+            node = parent[node];
+        }
+        node
+    }
+    for &(a, b) in links.keys() {
+        let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+        if ra != rb {
+            parent[ra.max(rb)] = ra.min(rb);
+        }
+    }
+
+    let mut groups: HashMap<usize, (Vec<usize>, Vec<f64>)> = HashMap::new();
+    for &(a, b) in links.keys() {
+        let group_root = root(&mut parent, a);
+        let group = groups.entry(group_root).or_default();
+        group.0.extend([a, b]);
+        group.1.push(links[&(a, b)]);
+    }
+    let mut clusters: Vec<DuplicateCluster> = groups
+        .into_values()
+        .map(|(mut members, scores)| {
+            members.sort_unstable();
+            members.dedup();
+            DuplicateCluster {
+                members,
+                mean_similarity: scores.iter().sum::<f64>() / scores.len() as f64,
+                min_similarity: scores.iter().cloned().fold(f64::INFINITY, f64::min),
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.members.len().cmp(&a.members.len()).then(a.members[0].cmp(&b.members[0])));
+    clusters
+}
@@ -0,0 +1,368 @@
+//! # RustySozluk Reference Graph
+//!
+//! Builds a directed graph of the references between titles or entries. References are read
+//! from the links captured when the entries were scraped (`Entry::links`): title searches such as
+//! `(bkz: başlık)` and backtick mentions point to titles, `/entry/12345` links and `(bkz: #12345)`
+//! searches to entries.
+//!
+//! Entries without captured links, for example ones loaded from CSV, fall back to the
+//! `(bkz: başlık)` and `(bkz: #12345)` references found in their text by `bkz_references`.
+//!
+//! Graphs can be exported to GraphML, GEXF (Gephi) and DOT (Graphviz), and come with in- and
+//! out-degrees and PageRank for every node.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, Write};
+use crate::analyzer::tokenizer::turkish_lowercase;
+use crate::util::escape_html;
+use crate::parser::{bkz_references, Entry};
+
+/// Damping factor used for the `pagerank` of the nodes.
+pub const DEFAULT_DAMPING: f64 = 0.85;
+
+/// The kind of the nodes of a `ReferenceGraph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum NodeKind {
+    Title,
+    Entry,
+}
+
+/// A title or entry in a `ReferenceGraph`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    /// Unique id of the node: the lowercased title, or the entry id.
+    pub id: String,
+    /// Human-readable label: the title as first seen, or `#<id>` for entries.
+    pub label: String,
+    /// Number of distinct nodes referencing this node.
+    pub in_degree: usize,
+    /// Number of distinct nodes this node references.
+    pub out_degree: usize,
+    /// PageRank with `DEFAULT_DAMPING`; the ranks of all nodes sum to 1.
+    pub pagerank: f64,
+}
+
+/// A reference from one node to another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    /// Index of the referencing node in `ReferenceGraph::nodes`.
+    pub source: usize,
+    /// Index of the referenced node in `ReferenceGraph::nodes`.
+    pub target: usize,
+    /// Number of references from `source` to `target`.
+    pub weight: usize,
+}
+
+/// A directed graph of references between titles or between entries.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::graph::ReferenceGraph;
+/// use rustysozluk::Entry;
+///
+/// let entry = |id: u64, title: &str, content: &str| Entry { id, title: title.to_string(), content: content.to_string(), ..Default::default() };
+/// let entries = vec![
+///     entry(1, "rust", "(bkz: cargo) ayrıca (bkz: go)"),
+///     entry(2, "go", "rust ile kıyaslanıyor (bkz: rust)"),
+///     entry(3, "cargo", "(bkz: #1)"),
+/// ];
+/// let graph = ReferenceGraph::titles(&entries);
+/// assert_eq!(graph.node("rust").unwrap().in_degree, 2);
+/// let mut dot = Vec::new();
+/// graph.write_dot(&mut dot).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReferenceGraph {
+    /// Whether the nodes are titles or entries.
+    pub kind: NodeKind,
+    pub nodes: Vec<GraphNode>,
+    /// Edges sorted by source and target.
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A reference found in an entry.
+enum Reference {
+    Title(String),
+    Entry(u64),
+}
+
+impl ReferenceGraph {
+    /// Builds the title-to-title graph. The title of an entry references every title it mentions;
+    /// references to entries count as references to the title of that entry when it is among
+    /// `entries`. Entries without a title and references of a title to itself are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` with their `title` set.
+    ///
+    /// # Returns
+    ///
+    /// A `ReferenceGraph` of titles.
+    pub fn titles(entries: &[Entry]) -> Self {
+        let entry_titles: HashMap<u64, &str> = entries.iter().filter(|e| e.id != 0 && !e.title.is_empty()).map(|e| (e.id, e.title.as_str())).collect();
+        let mut builder = Builder::default();
+        for entry in entries.iter().filter(|entry| !entry.title.is_empty()) {
+            let source = builder.node(&turkish_lowercase(entry.title.trim()), entry.title.trim());
+            for reference in references(entry) {
+                let title = match reference {
+                    Reference::Title(title) => title,
+                    Reference::Entry(id) => match entry_titles.get(&id) {
+                        Some(title) => title.trim().to_string(),
+                        None => continue,
+                    },
+                };
+                let target = builder.node(&turkish_lowercase(&title), &title);
+                builder.edge(source, target);
+            }
+        }
+        builder.finish(NodeKind::Title)
+    }
+
+    /// Builds the entry-to-entry graph from entry references (`/entry/<id>` links and `(bkz: #id)`
+    /// references). Entries with an unknown id (`0`) are ignored; referenced entries do not have
+    /// to be among `entries`.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` with their `id` set.
+    ///
+    /// # Returns
+    ///
+    /// A `ReferenceGraph` of entries.
+    pub fn entries(entries: &[Entry]) -> Self {
+        let mut builder = Builder::default();
+        for entry in entries.iter().filter(|entry| entry.id != 0) {
+            let source = builder.node(&entry.id.to_string(), &format!("#{}", entry.id));
+            for reference in references(entry) {
+                if let Reference::Entry(id) = reference {
+                    let target = builder.node(&id.to_string(), &format!("#{}", id));
+                    builder.edge(source, target);
+                }
+            }
+        }
+        builder.finish(NodeKind::Entry)
+    }
+
+    /// Returns the node with the given id (a lowercased title or an entry id).
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Returns the nodes sorted by descending PageRank, then by id.
+    pub fn ranked(&self) -> Vec<&GraphNode> {
+        let mut nodes: Vec<&GraphNode> = self.nodes.iter().collect();
+        nodes.sort_by(|a, b| b.pagerank.total_cmp(&a.pagerank).then_with(|| a.id.cmp(&b.id)));
+        nodes
+    }
+
+    /// Computes the PageRank of every node, weighting edges by their number of references.
+    /// Rank of nodes without outgoing edges is spread evenly over all nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `damping` - The probability of following a reference, usually `0.85`.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<f64>` with the rank of every node, in the order of `nodes`, summing to 1.
+    pub fn pagerank(&self, damping: f64) -> Vec<f64> {
+        let count = self.nodes.len();
+        if count == 0 {
+            return Vec::new();
+        }
+        let mut out_weight = vec![0.0; count];
+        for edge in &self.edges {
+            out_weight[edge.source] += edge.weight as f64;
+        }
+        let mut ranks = vec![1.0 / count as f64; count];
+        for _ in 0..100 {
+            let dangling: f64 = (0..count).filter(|&node| out_weight[node] == 0.0).map(|node| ranks[node]).sum();
+            let base = (1.0 - damping) / count as f64 + damping * dangling / count as f64;
+            let mut next = vec![base; count];
+            for edge in &self.edges {
+                next[edge.target] += damping * ranks[edge.source] * edge.weight as f64 / out_weight[edge.source];
+            }
+            let change: f64 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+            ranks = next;
+            if change < 1e-10 {
+                break;
+            }
+        }
+        ranks
+    }
+
+    /// Writes the graph as GraphML with `label`, `in_degree`, `out_degree` and `pagerank`
+    /// node attributes and a `weight` edge attribute.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination implementing `Write`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+    pub fn write_graphml<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        xml.push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"in_degree\" for=\"node\" attr.name=\"in_degree\" attr.type=\"int\"/>\n");
+        xml.push_str("  <key id=\"out_degree\" for=\"node\" attr.name=\"out_degree\" attr.type=\"int\"/>\n");
+        xml.push_str("  <key id=\"pagerank\" for=\"node\" attr.name=\"pagerank\" attr.type=\"double\"/>\n");
+        xml.push_str("  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>\n");
+        xml.push_str("  <graph edgedefault=\"directed\">\n");
+        for (index, node) in self.nodes.iter().enumerate() {
+            let _ = writeln!(
+                xml,
+                "    <node id=\"n{}\"><data key=\"label\">{}</data><data key=\"in_degree\">{}</data><data key=\"out_degree\">{}</data><data key=\"pagerank\">{}</data></node>",
+                index, escape_html(&node.label), node.in_degree, node.out_degree, node.pagerank
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(xml, "    <edge source=\"n{}\" target=\"n{}\"><data key=\"weight\">{}</data></edge>", edge.source, edge.target, edge.weight);
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        writer.write_all(xml.as_bytes())
+    }
+
+    /// Writes the graph as GEXF 1.3, the native format of Gephi.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination implementing `Write`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+    pub fn write_gexf<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+        xml.push_str("  <graph defaultedgetype=\"directed\">\n    <attributes class=\"node\">\n");
+        xml.push_str("      <attribute id=\"0\" title=\"in_degree\" type=\"integer\"/>\n");
+        xml.push_str("      <attribute id=\"1\" title=\"out_degree\" type=\"integer\"/>\n");
+        xml.push_str("      <attribute id=\"2\" title=\"pagerank\" type=\"double\"/>\n    </attributes>\n    <nodes>\n");
+        for (index, node) in self.nodes.iter().enumerate() {
+            let _ = writeln!(
+                xml,
+                "      <node id=\"{}\" label=\"{}\"><attvalues><attvalue for=\"0\" value=\"{}\"/><attvalue for=\"1\" value=\"{}\"/><attvalue for=\"2\" value=\"{}\"/></attvalues></node>",
+                index, escape_html(&node.label), node.in_degree, node.out_degree, node.pagerank
+            );
+        }
+        xml.push_str("    </nodes>\n    <edges>\n");
+        for (index, edge) in self.edges.iter().enumerate() {
+            let _ = writeln!(xml, "      <edge id=\"{}\" source=\"{}\" target=\"{}\" weight=\"{}\"/>", index, edge.source, edge.target, edge.weight);
+        }
+        xml.push_str("    </edges>\n  </graph>\n</gexf>\n");
+        writer.write_all(xml.as_bytes())
+    }
+
+    /// Writes the graph in the DOT language of Graphviz. Edges with more than one reference
+    /// are labelled with their weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination implementing `Write`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+    pub fn write_dot<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut dot = String::from("digraph references {\n");
+        for (index, node) in self.nodes.iter().enumerate() {
+            let _ = writeln!(dot, "  n{} [label={}];", index, quote(&node.label));
+        }
+        for edge in &self.edges {
+            if edge.weight > 1 {
+                let _ = writeln!(dot, "  n{} -> n{} [weight={}, label=\"{}\"];", edge.source, edge.target, edge.weight, edge.weight);
+            } else {
+                let _ = writeln!(dot, "  n{} -> n{};", edge.source, edge.target);
+            }
+        }
+        dot.push_str("}\n");
+        writer.write_all(dot.as_bytes())
+    }
+
+    /// Writes the graph to a GraphML file like `write_graphml`.
+    pub fn export_to_graphml(&self, file_name: &str) -> io::Result<()> {
+        self.write_graphml(File::create(file_name)?)
+    }
+
+    /// Writes the graph to a GEXF file like `write_gexf`.
+    pub fn export_to_gexf(&self, file_name: &str) -> io::Result<()> {
+        self.write_gexf(File::create(file_name)?)
+    }
+
+    /// Writes the graph to a DOT file like `write_dot`.
+    pub fn export_to_dot(&self, file_name: &str) -> io::Result<()> {
+        self.write_dot(File::create(file_name)?)
+    }
+}
+
+/// Collects nodes and weighted edges before the metrics are computed.
+#[derive(Default)]
+struct Builder {
+    nodes: Vec<(String, String)>,
+    index: HashMap<String, usize>,
+    edges: HashMap<(usize, usize), usize>,
+}
+
+impl Builder {
+    fn node(&mut self, id: &str, label: &str) -> usize {
+        if let Some(&index) = self.index.get(id) {
+            return index;
+        }
+        self.nodes.push((id.to_string(), label.to_string()));
+        self.index.insert(id.to_string(), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    fn edge(&mut self, source: usize, target: usize) {
+        if source != target {
+            *self.edges.entry((source, target)).or_insert(0) += 1;
+        }
+    }
+
+    fn finish(self, kind: NodeKind) -> ReferenceGraph {
+        let mut edges: Vec<GraphEdge> = self.edges.into_iter().map(|((source, target), weight)| GraphEdge { source, target, weight }).collect();
+        edges.sort_by_key(|edge| (edge.source, edge.target));
+        let mut nodes: Vec<GraphNode> = self
+            .nodes
+            .into_iter()
+            .map(|(id, label)| GraphNode { id, label, in_degree: 0, out_degree: 0, pagerank: 0.0 })
+            .collect();
+        for edge in &edges {
+            nodes[edge.source].out_degree += 1;
+            nodes[edge.target].in_degree += 1;
+        }
+        let mut graph = ReferenceGraph { kind, nodes, edges };
+        let ranks = graph.pagerank(DEFAULT_DAMPING);
+        for (node, rank) in graph.nodes.iter_mut().zip(ranks) {
+            node.pagerank = rank;
+        }
+        graph
+    }
+}
+
+/// Returns the references of an entry from its links, or from the `(bkz: ...)` references in its
+/// text when no links were captured.
+fn references(entry: &Entry) -> Vec<Reference> {
+    if !entry.links.is_empty() {
+        return entry
+            .links
+            .iter()
+            .filter_map(|link| match link.entry_id() {
+                Some(id) => Some(Reference::Entry(id)),
+                None => link.title().map(Reference::Title),
+            })
+            .collect();
+    }
+    bkz_references(&entry.content)
+        .into_iter()
+        .map(|reference| match reference.entry_id() {
+            Some(id) => Reference::Entry(id),
+            None => Reference::Title(reference.target),
+        })
+        .collect()
+}
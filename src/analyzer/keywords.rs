@@ -0,0 +1,368 @@
+//! # RustySozluk Keywords
+//!
+//! Finds the words that are distinctive for a document rather than merely frequent. Entries are
+//! grouped into documents (one per entry, title, user or time window) and scored with one of:
+//!
+//! * TF-IDF: frequent in the document but rare in the other documents. With titles as documents
+//!   this answers "what is this title about compared with the others"; with users, "what does this
+//!   user talk about more than average".
+//! * RAKE: phrases between stopwords, scored by how many other words their words co-occur with.
+//! * YAKE-style: statistical features of a single document (casing, position, spread over
+//!   sentences and variety of neighbors), so no other documents are needed.
+
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::analyzer::ngrams::segments;
+use crate::analyzer::stemmer::Stemmer;
+use crate::analyzer::tokenizer::{clause_ids, TokenKind, Tokenizer};
+use crate::parser::Entry;
+
+/// How entries are grouped into documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocumentGrouping {
+    /// Every entry is a document, named by its id (or its position if the id is unknown).
+    #[default]
+    Entry,
+    /// Entries of the same title form a document, named by the title.
+    Title,
+    /// Entries of the same author form a document, named by the username.
+    User,
+    /// Entries created in the same time window form a document, named by the window start
+    /// (`2023-05-01 00:00`). Windows are aligned to the Unix epoch; entries without a parsable
+    /// date are left out.
+    Window(Duration),
+}
+
+/// The keyword scoring algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum KeywordMethod {
+    /// Term frequency times smoothed inverse document frequency.
+    #[default]
+    TfIdf,
+    /// Rapid Automatic Keyword Extraction; keywords are phrases of up to `max_phrase_words` words.
+    Rake,
+    /// A YAKE-style single-document score. Higher is more relevant, so the score is the inverse
+    /// of the original YAKE weight.
+    Yake,
+}
+
+/// A keyword of a document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Keyword {
+    /// The word or phrase, stemmed if a stemmer was set.
+    pub term: String,
+    /// Relevance under the chosen `KeywordMethod`; higher is more relevant.
+    pub score: f64,
+    /// Number of occurrences in the document.
+    pub frequency: usize,
+}
+
+/// The keywords of one document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocumentKeywords {
+    /// The name of the document: an entry id, title, username or window start.
+    pub document: String,
+    /// Number of entries in the document.
+    pub entries: usize,
+    /// The best keywords, sorted by descending score, then by descending frequency and term.
+    pub keywords: Vec<Keyword>,
+}
+
+/// Extracts keywords from entries grouped into documents.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::keywords::{DocumentGrouping, KeywordExtractor};
+/// use rustysozluk::Entry;
+///
+/// let entry = |title: &str, content: &str| Entry { title: title.to_string(), content: content.to_string(), ..Default::default() };
+/// let entries = vec![
+///     entry("rust", "rust derleyicisi hızlı"),
+///     entry("rust", "rust hataları anlaşılır"),
+///     entry("go", "go derleyicisi hızlı"),
+/// ];
+/// let documents = KeywordExtractor::new().grouping(DocumentGrouping::Title).extract(&entries, &HashSet::new());
+/// assert_eq!(documents[0].document, "rust");
+/// assert_eq!(documents[0].keywords[0].term, "rust");
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeywordExtractor {
+    grouping: DocumentGrouping,
+    method: KeywordMethod,
+    max_keywords: usize,
+    max_phrase_words: usize,
+    min_frequency: usize,
+    stemmer: Option<Stemmer>,
+}
+
+impl Default for KeywordExtractor {
+    fn default() -> Self {
+        KeywordExtractor::new()
+    }
+}
+
+impl KeywordExtractor {
+    /// Creates an extractor that scores every entry with TF-IDF and keeps 10 keywords per document.
+    pub fn new() -> Self {
+        KeywordExtractor {
+            grouping: DocumentGrouping::Entry,
+            method: KeywordMethod::TfIdf,
+            max_keywords: 10,
+            max_phrase_words: 3,
+            min_frequency: 1,
+            stemmer: None,
+        }
+    }
+
+    /// Sets how entries are grouped into documents.
+    pub fn grouping(mut self, grouping: DocumentGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Sets the scoring algorithm.
+    pub fn method(mut self, method: KeywordMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Sets the number of keywords kept per document.
+    pub fn max_keywords(mut self, max_keywords: usize) -> Self {
+        self.max_keywords = max_keywords;
+        self
+    }
+
+    /// Sets the longest RAKE phrase; longer runs of words are split into phrases of this length.
+    pub fn max_phrase_words(mut self, max_phrase_words: usize) -> Self {
+        self.max_phrase_words = max_phrase_words.max(1);
+        self
+    }
+
+    /// Sets how often a keyword has to occur in a document to be returned.
+    pub fn min_frequency(mut self, min_frequency: usize) -> Self {
+        self.min_frequency = min_frequency;
+        self
+    }
+
+    /// Sets the stemmer applied to every word; `None` (the default) keeps words as written.
+    pub fn stemmer(mut self, stemmer: Option<Stemmer>) -> Self {
+        self.stemmer = stemmer;
+        self
+    }
+
+    /// Groups the entries into documents and extracts the keywords of each.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to analyze.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<DocumentKeywords>` with one element per document, in order of first appearance
+    /// (in chronological order for time windows).
+    pub fn extract(&self, entries: &[Entry], stopwords: &HashSet<String>) -> Vec<DocumentKeywords> {
+        let documents = self.group(entries);
+        let mut results: Vec<DocumentKeywords> = match self.method {
+            KeywordMethod::TfIdf => self.tf_idf(&documents, stopwords),
+            KeywordMethod::Rake => documents.iter().map(|(name, texts)| self.rake(name, texts, stopwords)).collect(),
+            KeywordMethod::Yake => documents.iter().map(|(name, texts)| self.yake(name, texts, stopwords)).collect(),
+        };
+        for result in &mut results {
+            result.keywords.retain(|keyword| keyword.frequency >= self.min_frequency);
+            result.keywords.sort_by(|a, b| {
+                b.score
+                    .total_cmp(&a.score)
+                    .then(b.frequency.cmp(&a.frequency))
+                    .then_with(|| a.term.cmp(&b.term))
+            });
+            result.keywords.truncate(self.max_keywords);
+        }
+        results
+    }
+
+    /// Returns the documents as names with the contents of their entries.
+    fn group<'a>(&self, entries: &'a [Entry]) -> Vec<(String, Vec<&'a str>)> {
+        let mut documents: Vec<(String, Vec<&str>)> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut window_starts: HashMap<String, NaiveDateTime> = HashMap::new();
+        for (index, entry) in entries.iter().enumerate() {
+            let name = match self.grouping {
+                DocumentGrouping::Entry if entry.id != 0 => entry.id.to_string(),
+                DocumentGrouping::Entry => index.to_string(),
+                DocumentGrouping::Title => entry.title.clone(),
+                DocumentGrouping::User => entry.username.clone(),
+                DocumentGrouping::Window(window) => {
+                    let Some(start) = entry.created_at().and_then(|created| window_start(created, window)) else { continue };
+                    let name = start.format("%Y-%m-%d %H:%M").to_string();
+                    window_starts.insert(name.clone(), start);
+                    name
+                }
+            };
+            let position = *positions.entry(name.clone()).or_insert_with(|| {
+                documents.push((name, Vec::new()));
+                documents.len() - 1
+            });
+            documents[position].1.push(&entry.content);
+        }
+        if matches!(self.grouping, DocumentGrouping::Window(_)) {
+            documents.sort_by_key(|(name, _)| window_starts[name]);
+        }
+        documents
+    }
+
+    fn tf_idf(&self, documents: &[(String, Vec<&str>)], stopwords: &HashSet<String>) -> Vec<DocumentKeywords> {
+        let counts: Vec<HashMap<String, usize>> = documents
+            .iter()
+            .map(|(_, texts)| {
+                let mut counts = HashMap::new();
+                for segment in texts.iter().flat_map(|text| segments(text, stopwords, self.stemmer.as_ref())) {
+                    for word in segment {
+                        *counts.entry(word).or_insert(0) += 1;
+                    }
+                }
+                counts
+            })
+            .collect();
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        for word in counts.iter().flat_map(|counts| counts.keys()) {
+            *document_frequency.entry(word).or_insert(0) += 1;
+        }
+        let total_documents = documents.len() as f64;
+        documents
+            .iter()
+            .zip(&counts)
+            .map(|((name, texts), counts)| {
+                let length = counts.values().sum::<usize>().max(1) as f64;
+                let keywords = counts
+                    .iter()
+                    .map(|(word, &frequency)| {
+                        let idf = ((1.0 + total_documents) / (1.0 + document_frequency[word.as_str()] as f64)).ln() + 1.0;
+                        Keyword { term: word.clone(), score: frequency as f64 / length * idf, frequency }
+                    })
+                    .collect();
+                DocumentKeywords { document: name.clone(), entries: texts.len(), keywords }
+            })
+            .collect()
+    }
+
+    fn rake(&self, name: &str, texts: &[&str], stopwords: &HashSet<String>) -> DocumentKeywords {
+        let phrases: Vec<Vec<String>> = texts
+            .iter()
+            .flat_map(|text| segments(text, stopwords, self.stemmer.as_ref()))
+            .flat_map(|segment| segment.chunks(self.max_phrase_words).map(<[String]>::to_vec).collect::<Vec<_>>())
+            .collect();
+        let mut frequency: HashMap<&str, f64> = HashMap::new();
+        let mut degree: HashMap<&str, f64> = HashMap::new();
+        let mut phrase_counts: HashMap<&[String], usize> = HashMap::new();
+        for phrase in &phrases {
+            *phrase_counts.entry(phrase).or_insert(0) += 1;
+            for word in phrase {
+                *frequency.entry(word).or_insert(0.0) += 1.0;
+                *degree.entry(word).or_insert(0.0) += phrase.len() as f64;
+            }
+        }
+        let keywords = phrase_counts
+            .into_iter()
+            .map(|(phrase, count)| Keyword {
+                term: phrase.join(" "),
+                score: phrase.iter().map(|word| degree[word.as_str()] / frequency[word.as_str()]).sum(),
+                frequency: count,
+            })
+            .collect();
+        DocumentKeywords { document: name.to_string(), entries: texts.len(), keywords }
+    }
+
+    fn yake(&self, name: &str, texts: &[&str], stopwords: &HashSet<String>) -> DocumentKeywords {
+        #[derive(Default)]
+        struct Stats {
+            frequency: usize,
+            capitalized: usize,
+            sentences: Vec<usize>,
+            left: HashMap<String, usize>,
+            right: HashMap<String, usize>,
+        }
+
+        let tokenizer = Tokenizer::new();
+        let mut stats: HashMap<String, Stats> = HashMap::new();
+        let mut sentence_count = 0;
+        for text in texts {
+            let tokens = tokenizer.tokenize(text);
+            let clauses = clause_ids(text, &tokens);
+            let mut previous: Option<String> = None;
+            let mut sentence_start = true;
+            let mut current_clause = 0;
+            for (token, &clause) in tokens.iter().zip(&clauses) {
+                let sentence = sentence_count + clause;
+                if clause != current_clause {
+                    previous = None;
+                    sentence_start = true;
+                    current_clause = clause;
+                }
+                if token.kind != TokenKind::Word || stopwords.contains(&token.text) {
+                    previous = None;
+                    sentence_start = false;
+                    continue;
+                }
+                let term = match &self.stemmer {
+                    Some(stemmer) => stemmer.stem(&token.text),
+                    None => token.text.clone(),
+                };
+                let term_stats = stats.entry(term.clone()).or_default();
+                term_stats.frequency += 1;
+                if !sentence_start && token.raw.starts_with(char::is_uppercase) {
+                    term_stats.capitalized += 1;
+                }
+                term_stats.sentences.push(sentence);
+                if let Some(left) = &previous {
+                    *term_stats.left.entry(left.clone()).or_insert(0) += 1;
+                    *stats.get_mut(left).expect("left neighbor was counted").right.entry(term.clone()).or_insert(0) += 1;
+                }
+                previous = Some(term);
+                sentence_start = false;
+            }
+            sentence_count += clauses.last().map_or(0, |last| last + 1);
+        }
+
+        let frequencies: Vec<f64> = stats.values().map(|s| s.frequency as f64).collect();
+        let count = frequencies.len().max(1) as f64;
+        let mean = frequencies.iter().sum::<f64>() / count;
+        let deviation = (frequencies.iter().map(|f| (f - mean).powi(2)).sum::<f64>() / count).sqrt();
+        let max_frequency = frequencies.iter().cloned().fold(1.0, f64::max);
+        let diversity = |neighbors: &HashMap<String, usize>| {
+            let total: usize = neighbors.values().sum();
+            if total == 0 { 0.0 } else { neighbors.len() as f64 / total as f64 }
+        };
+
+        let keywords = stats
+            .into_iter()
+            .map(|(term, mut s)| {
+                let frequency = s.frequency as f64;
+                s.sentences.sort_unstable();
+                let median = s.sentences[s.sentences.len() / 2] as f64;
+                s.sentences.dedup();
+                let casing = s.capitalized as f64 / (1.0 + frequency.ln());
+                let position = (3.0 + median).ln().ln();
+                let normalized_frequency = frequency / (mean + deviation);
+                let relatedness = 1.0 + (diversity(&s.left) + diversity(&s.right)) * frequency / max_frequency;
+                let spread = s.sentences.len() as f64 / sentence_count.max(1) as f64;
+                let weight = relatedness * position / (casing + normalized_frequency / relatedness + spread / relatedness);
+                Keyword { term, score: 1.0 / weight, frequency: s.frequency }
+            })
+            .collect();
+        DocumentKeywords { document: name.to_string(), entries: texts.len(), keywords }
+    }
+}
+
+/// Returns the start of the epoch-aligned window of the given length that contains `time`.
+fn window_start(time: NaiveDateTime, window: Duration) -> Option<NaiveDateTime> {
+    let seconds = window.num_seconds();
+    if seconds <= 0 {
+        return None;
+    }
+    let timestamp = time.and_utc().timestamp();
+    chrono::DateTime::from_timestamp(timestamp - timestamp.rem_euclid(seconds), 0).map(|start| start.naive_utc())
+}
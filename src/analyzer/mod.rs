@@ -1 +1,20 @@
-pub mod analyzer;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod analyzer;
+pub mod authors;
+pub mod classifier;
+pub mod dedup;
+pub mod graph;
+pub mod keywords;
+pub mod ner;
+pub mod ngrams;
+pub mod readability;
+#[cfg(feature = "embedded-resources")]
+pub mod resources;
+mod rng;
+pub mod sentiment;
+pub mod similarity;
+pub mod stemmer;
+pub mod timeseries;
+pub mod tokenizer;
+pub mod topics;
+pub mod wordcloud;
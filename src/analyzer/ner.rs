@@ -0,0 +1,588 @@
+//! # RustySozluk Named Entities
+//!
+//! Finds the people, places and organizations mentioned in entries. Two sources of evidence are
+//! combined:
+//!
+//! * A **gazetteer** of known names with their aliases (`Erdoğan`, `CHP`, `Antep`), each mapped to
+//!   a canonical name and a kind. The bundled one lists the 81 provinces, other well-known places,
+//!   politicians and organizations; `read_gazetteer` loads more from a CSV.
+//! * **Heuristics** on the text as written, which the lowercasing preprocessing would lose:
+//!   runs of capitalized words inside a sentence (`Orhan Pamuk`) and words with an apostrophe
+//!   suffix (`pamuk'un`), which Turkish spelling reserves for proper nouns.
+//!
+//! Gazetteer names that are also common words (`ordu`, `van`, `aydın`) are only recognized when
+//! capitalized inside a sentence or followed by an apostrophe suffix.
+//!
+//! The results are per-entry entity lists and entity frequency tables, both exportable as CSV.
+
+use csv::{ReaderBuilder, Writer};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+#[cfg(feature = "embedded-resources")]
+use crate::analyzer::resources::default_gazetteer;
+use crate::analyzer::tokenizer::{turkish_lowercase, Token, TokenKind, Tokenizer};
+use crate::parser::Entry;
+
+/// Words in all caps longer than this are taken as shouting, not as acronyms.
+const MAX_ACRONYM_LENGTH: usize = 5;
+
+/// The kind of a named entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    /// A person, e.g. a politician.
+    Person,
+    /// A city, province, country or other place.
+    Place,
+    /// A party, institution, club or company.
+    Organization,
+    /// A proper noun found by the heuristics that is not in the gazetteer.
+    Other,
+}
+
+impl EntityKind {
+    /// Returns the lowercase name used in gazetteer and output CSV files.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityKind::Person => "person",
+            EntityKind::Place => "place",
+            EntityKind::Organization => "organization",
+            EntityKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// How a mention was recognized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntitySource {
+    /// The words are a name or alias in the gazetteer.
+    Gazetteer,
+    /// The words are capitalized inside a sentence.
+    Capitalization,
+    /// The word has an apostrophe suffix.
+    Apostrophe,
+}
+
+impl EntitySource {
+    /// Returns the lowercase name used in output CSV files.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntitySource::Gazetteer => "gazetteer",
+            EntitySource::Capitalization => "capitalization",
+            EntitySource::Apostrophe => "apostrophe",
+        }
+    }
+}
+
+/// A name of a gazetteer, stored under each of its aliases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GazetteerEntry {
+    /// The canonical name every alias is reported as (`Recep Tayyip Erdoğan` for `Erdoğan`).
+    pub name: String,
+    /// The kind of the entity.
+    pub kind: EntityKind,
+    /// Whether the alias is also a common word and needs capitalization or an apostrophe suffix to count.
+    pub ambiguous: bool,
+}
+
+/// A list of known names and aliases, looked up case-insensitively.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::ner::{EntityKind, Gazetteer, GazetteerEntry};
+///
+/// let mut gazetteer = Gazetteer::new();
+/// let pamuk = GazetteerEntry { name: "Orhan Pamuk".to_string(), kind: EntityKind::Person, ambiguous: false };
+/// gazetteer.insert("Orhan Pamuk", pamuk.clone());
+/// gazetteer.insert("Pamuk", GazetteerEntry { ambiguous: true, ..pamuk });
+/// assert_eq!(gazetteer.get("ORHAN PAMUK").unwrap().kind, EntityKind::Person);
+/// assert!(gazetteer.get("pamuk").unwrap().ambiguous);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gazetteer {
+    names: HashMap<String, GazetteerEntry>,
+    max_words: usize,
+}
+
+impl Gazetteer {
+    /// Creates an empty gazetteer.
+    pub fn new() -> Self {
+        Gazetteer::default()
+    }
+
+    /// Adds an alias, replacing an earlier entry for the same alias. Aliases without any word are ignored.
+    pub fn insert(&mut self, alias: &str, entry: GazetteerEntry) {
+        let key = name_key(alias);
+        if key.is_empty() {
+            return;
+        }
+        self.max_words = self.max_words.max(key.split(' ').count());
+        self.names.insert(key, entry);
+    }
+
+    /// Looks up an alias, ignoring case and apostrophe suffixes.
+    pub fn get(&self, alias: &str) -> Option<&GazetteerEntry> {
+        self.names.get(&name_key(alias))
+    }
+
+    /// Adds every alias of another gazetteer, replacing entries for the same aliases.
+    pub fn extend(&mut self, other: Gazetteer) {
+        self.max_words = self.max_words.max(other.max_words);
+        self.names.extend(other.names);
+    }
+
+    /// Number of aliases.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether the gazetteer has no aliases.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Reads a gazetteer from a CSV file.
+///
+/// The file has a `NAME,KIND,CANONICAL,AMBIGUOUS` header. `KIND` is `person`, `place`,
+/// `organization` or `other`. `CANONICAL` is the name the alias is reported as and defaults to
+/// `NAME`; `AMBIGUOUS` is `yes`, `true` or `1` for names that are also common words. The last
+/// two columns may be left empty or out.
+///
+/// # Arguments
+///
+/// * `file_path` - Path to the CSV file.
+///
+/// # Returns
+///
+/// A `Gazetteer`, which can be merged into another one with `Gazetteer::extend`.
+pub fn read_gazetteer<P: AsRef<Path>>(file_path: P) -> io::Result<Gazetteer> {
+    read_gazetteer_from(File::open(file_path)?)
+}
+
+/// Reads a gazetteer from any reader, in the same format as `read_gazetteer`.
+///
+/// # Arguments
+///
+/// * `reader` - The source implementing `Read`.
+///
+/// # Returns
+///
+/// A `Gazetteer`, which can be merged into another one with `Gazetteer::extend`.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::ner::{read_gazetteer_from, EntityKind};
+///
+/// let csv = "NAME,KIND,CANONICAL,AMBIGUOUS\nOrhan Pamuk,person\nPamuk,person,Orhan Pamuk,yes\n";
+/// let gazetteer = read_gazetteer_from(csv.as_bytes()).unwrap();
+/// assert_eq!(gazetteer.get("pamuk").unwrap().name, "Orhan Pamuk");
+/// ```
+pub fn read_gazetteer_from<R: Read>(reader: R) -> io::Result<Gazetteer> {
+    let mut gazetteer = Gazetteer::new();
+    let mut rdr = ReaderBuilder::new().flexible(true).from_reader(reader);
+    for result in rdr.records() {
+        let record = result?;
+        let name = record.get(0).unwrap_or("").trim();
+        let kind = match record.get(1).unwrap_or("").trim().to_lowercase().as_str() {
+            "person" => EntityKind::Person,
+            "place" => EntityKind::Place,
+            "organization" => EntityKind::Organization,
+            "other" => EntityKind::Other,
+            other => return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unknown entity kind `{}` for `{}`", other, name))),
+        };
+        let canonical = record.get(2).map(str::trim).filter(|canonical| !canonical.is_empty()).unwrap_or(name);
+        let ambiguous = matches!(record.get(3).unwrap_or("").trim().to_lowercase().as_str(), "yes" | "true" | "1");
+        gazetteer.insert(name, GazetteerEntry { name: canonical.to_string(), kind, ambiguous });
+    }
+    Ok(gazetteer)
+}
+
+/// A named entity found in a text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntityMention {
+    /// The canonical name from the gazetteer, or the words as written without an apostrophe suffix.
+    pub name: String,
+    /// The kind of the entity.
+    pub kind: EntityKind,
+    /// How the mention was recognized.
+    pub source: EntitySource,
+    /// The mention exactly as it appears in the text, including any apostrophe suffix.
+    pub text: String,
+    /// Byte offset of the mention in the text.
+    pub offset: usize,
+}
+
+/// The entities mentioned in one entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EntryEntities {
+    /// Position of the entry in the analyzed list.
+    pub index: usize,
+    /// Eksi Sozluk id of the entry, `0` if unknown.
+    pub entry_id: u64,
+    /// The mentions in the order they appear.
+    pub entities: Vec<EntityMention>,
+}
+
+/// How often an entity is mentioned in a collection of entries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntityFrequency {
+    /// The name of the entity, as in `EntityMention::name`.
+    pub name: String,
+    /// The kind of the entity.
+    pub kind: EntityKind,
+    /// Number of mentions.
+    pub mentions: usize,
+    /// Number of entries with at least one mention.
+    pub entries: usize,
+}
+
+/// Recognizes named entities in entries.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::ner::{read_gazetteer_from, EntityKind, EntityRecognizer, EntitySource};
+///
+/// let gazetteer = read_gazetteer_from("NAME,KIND\nAnkara,place\n".as_bytes()).unwrap();
+/// let recognizer = EntityRecognizer::new(gazetteer);
+/// let mentions = recognizer.recognize_text("Dün ankara'da Orhan Pamuk ile karşılaştık.");
+///
+/// assert_eq!(mentions[0].name, "Ankara");
+/// assert_eq!(mentions[0].kind, EntityKind::Place);
+/// assert_eq!(mentions[0].text, "ankara'da");
+/// assert_eq!(mentions[1].name, "Orhan Pamuk");
+/// assert_eq!(mentions[1].source, EntitySource::Capitalization);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EntityRecognizer {
+    gazetteer: Cow<'static, Gazetteer>,
+    capitalization: bool,
+    apostrophes: bool,
+}
+
+/// A token with what its surroundings tell about it.
+struct Word<'a> {
+    token: &'a Token,
+    /// Whether the token starts a sentence.
+    sentence_start: bool,
+    /// Whether the token directly continues the name before it: only whitespace in between
+    /// and no apostrophe suffix on the previous word.
+    joined: bool,
+    /// Whether the token starts with a capital letter and is not part of a text written in all caps.
+    capitalized: bool,
+}
+
+impl EntityRecognizer {
+    /// Creates a recognizer with the given gazetteer and both heuristics enabled.
+    pub fn new(gazetteer: Gazetteer) -> Self {
+        EntityRecognizer { gazetteer: Cow::Owned(gazetteer), capitalization: true, apostrophes: true }
+    }
+
+    /// Creates a recognizer that uses the bundled gazetteer. The gazetteer is shared, not copied,
+    /// so this is cheap to call repeatedly.
+    #[cfg(feature = "embedded-resources")]
+    pub fn embedded() -> Self {
+        EntityRecognizer { gazetteer: Cow::Borrowed(default_gazetteer()), capitalization: true, apostrophes: true }
+    }
+
+    /// Sets whether runs of capitalized words inside a sentence are reported as entities.
+    pub fn capitalization(mut self, capitalization: bool) -> Self {
+        self.capitalization = capitalization;
+        self
+    }
+
+    /// Sets whether words with an apostrophe suffix (`pamuk'un`) are reported as entities.
+    pub fn apostrophes(mut self, apostrophes: bool) -> Self {
+        self.apostrophes = apostrophes;
+        self
+    }
+
+    /// Returns the gazetteer used by the recognizer.
+    pub fn gazetteer(&self) -> &Gazetteer {
+        &self.gazetteer
+    }
+
+    /// Recognizes the entities of a list of entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to analyze.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<EntryEntities>` with one item per entry, in input order.
+    pub fn recognize(&self, entries: &[Entry]) -> Vec<EntryEntities> {
+        entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| EntryEntities { index, entry_id: entry.id, entities: self.recognize_text(&entry.content) })
+            .collect()
+    }
+
+    /// Recognizes the entities of a single piece of text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string slice containing the text to analyze.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<EntityMention>` in the order the mentions appear. Mentions do not overlap; where
+    /// several could start at a word, the longest is taken.
+    pub fn recognize_text(&self, text: &str) -> Vec<EntityMention> {
+        let tokens = Tokenizer::new().tokenize(text);
+        let mut words = Vec::with_capacity(tokens.len());
+        let mut previous: Option<&Token> = None;
+        for token in &tokens {
+            let previous_end = previous.map_or(0, |previous| previous.offset + previous.raw.len());
+            let gap = text.get(previous_end..token.offset).unwrap_or("");
+            words.push(Word {
+                token,
+                sentence_start: previous.is_none() || gap.contains(['.', '!', '?', '…', ':', '\n']),
+                joined: previous.is_some_and(|previous| previous.kind == TokenKind::Word && previous.suffix.is_none()) && gap.trim().is_empty(),
+                capitalized: token.raw.starts_with(char::is_uppercase),
+            });
+            previous = Some(token);
+        }
+        // Acronyms stand alone; neighbouring words in all caps are shouting.
+        let all_caps: Vec<bool> = words.iter().map(|word| is_all_caps(word.token)).collect();
+        for index in 0..words.len() {
+            let shouting = (index > 0 && words[index].joined && all_caps[index - 1])
+                || (index + 1 < words.len() && words[index + 1].joined && all_caps[index + 1]);
+            let letters = words[index].token.raw.chars().filter(|c| c.is_alphabetic()).count();
+            if all_caps[index] && (shouting || letters > MAX_ACRONYM_LENGTH) {
+                words[index].capitalized = false;
+            }
+        }
+
+        let mut mentions = Vec::new();
+        let mut position = 0;
+        while position < words.len() {
+            if words[position].token.kind != TokenKind::Word {
+                position += 1;
+                continue;
+            }
+            let found = self.gazetteer_match(&words, position);
+            let run = if self.capitalization { capitalized_run(&words, position) } else { 0 };
+            if let Some((length, entry)) = found.filter(|(length, _)| *length >= run) {
+                mentions.push(mention(text, &words[position..position + length], entry.name.clone(), entry.kind, EntitySource::Gazetteer));
+                position += length;
+            } else if run > 0 {
+                let suffixed = self.apostrophes && words[position + run - 1].token.suffix.is_some();
+                if words[position].sentence_start && found.is_none() && !(run == 1 && suffixed) {
+                    // A capital at the start of a sentence says nothing; the rest of the run may still be a name.
+                    position += 1;
+                    continue;
+                }
+                let span = &words[position..position + run];
+                let source = if words[position].sentence_start && found.is_none() { EntitySource::Apostrophe } else { EntitySource::Capitalization };
+                let tail = (1..run).find_map(|start| self.gazetteer.names.get(&span_key(&span[start..])).map(|entry| (start, entry)));
+                match tail {
+                    // A known surname makes the whole run a person (`Ahmet Erdoğan`).
+                    Some((_, entry)) if entry.kind == EntityKind::Person => {
+                        mentions.push(mention(text, span, written_name(span), EntityKind::Person, source));
+                    }
+                    // A known place or organization after a name is a separate entity (`Orhan Pamuk Kadıköy'de`).
+                    Some((start, entry)) => {
+                        let head = &span[..start];
+                        mentions.push(match self.gazetteer.names.get(&span_key(head)) {
+                            Some(known) => mention(text, head, known.name.clone(), known.kind, EntitySource::Gazetteer),
+                            None => mention(text, head, written_name(head), EntityKind::Other, source),
+                        });
+                        mentions.push(mention(text, &span[start..], entry.name.clone(), entry.kind, EntitySource::Gazetteer));
+                    }
+                    None => mentions.push(mention(text, span, written_name(span), EntityKind::Other, source)),
+                }
+                position += run;
+            } else if self.apostrophes && words[position].token.suffix.is_some() {
+                let span = &words[position..position + 1];
+                mentions.push(mention(text, span, written_name(span), EntityKind::Other, EntitySource::Apostrophe));
+                position += 1;
+            } else {
+                position += 1;
+            }
+        }
+        mentions
+    }
+
+    /// Finds the longest gazetteer name starting at `position`, skipping ambiguous names
+    /// without capitalization or an apostrophe suffix.
+    fn gazetteer_match(&self, words: &[Word], position: usize) -> Option<(usize, &GazetteerEntry)> {
+        let longest = self.gazetteer.max_words.min(words.len() - position);
+        (1..=longest).rev().find_map(|length| {
+            let span = &words[position..position + length];
+            if span.iter().any(|word| word.token.kind != TokenKind::Word) || span[1..].iter().any(|word| !word.joined) {
+                return None;
+            }
+            let entry = self.gazetteer.names.get(&span_key(span))?;
+            let evident = (span[0].capitalized && !span[0].sentence_start) || span[length - 1].token.suffix.is_some();
+            (!entry.ambiguous || evident).then_some((length, entry))
+        })
+    }
+}
+
+/// Counts the mentions of every entity, most mentioned first.
+///
+/// Mentions are grouped by name, ignoring case, and kind; the name is reported as first written.
+///
+/// # Arguments
+///
+/// * `entries` - The per-entry entities, e.g. from `EntityRecognizer::recognize`.
+///
+/// # Returns
+///
+/// A `Vec<EntityFrequency>` sorted by mentions, then by the number of entries, then by name.
+pub fn entity_frequencies(entries: &[EntryEntities]) -> Vec<EntityFrequency> {
+    let mut frequencies: Vec<EntityFrequency> = Vec::new();
+    let mut positions: HashMap<(String, EntityKind), usize> = HashMap::new();
+    for entry in entries {
+        let mut seen = HashSet::new();
+        for entity in &entry.entities {
+            let key = (turkish_lowercase(&entity.name), entity.kind);
+            let position = *positions.entry(key.clone()).or_insert_with(|| {
+                frequencies.push(EntityFrequency { name: entity.name.clone(), kind: entity.kind, mentions: 0, entries: 0 });
+                frequencies.len() - 1
+            });
+            frequencies[position].mentions += 1;
+            if seen.insert(key) {
+                frequencies[position].entries += 1;
+            }
+        }
+    }
+    frequencies.sort_by(|a, b| {
+        b.mentions.cmp(&a.mentions).then(b.entries.cmp(&a.entries)).then_with(|| a.name.cmp(&b.name)).then(a.kind.cmp(&b.kind))
+    });
+    frequencies
+}
+
+/// Prints the `n` most mentioned entities as a table.
+///
+/// # Arguments
+///
+/// * `frequencies` - The entity frequencies, e.g. from `entity_frequencies`.
+/// * `n` - The number of entities to print.
+pub fn print_entity_frequencies(frequencies: &[EntityFrequency], n: usize) {
+    let width = frequencies.iter().take(n).map(|entity| entity.name.chars().count()).max().unwrap_or(0).max(15);
+    println!("Top {} entities:", n);
+    println!("{:<10} {:<width$} {:<14} {:<10} Entries", "Rank", "Entity", "Kind", "Mentions", width = width);
+    println!("{}", "-".repeat(width + 45));
+    for (idx, entity) in frequencies.iter().enumerate().take(n) {
+        println!("{:<10} {:<width$} {:<14} {:<10} {}", idx + 1, entity.name, entity.kind, entity.mentions, entity.entries, width = width);
+    }
+}
+
+/// Writes entity frequencies as CSV with the columns `name`, `kind`, `mentions` and `entries`.
+///
+/// # Arguments
+///
+/// * `frequencies` - The entity frequencies, e.g. from `entity_frequencies`.
+/// * `writer` - The destination implementing `Write`.
+pub fn write_entity_frequencies_csv<W: Write>(frequencies: &[EntityFrequency], writer: W) -> io::Result<()> {
+    let mut wtr = Writer::from_writer(writer);
+    wtr.write_record(["name", "kind", "mentions", "entries"])?;
+    for entity in frequencies {
+        wtr.write_record([entity.name.as_str(), entity.kind.as_str(), &entity.mentions.to_string(), &entity.entries.to_string()])?;
+    }
+    wtr.flush()
+}
+
+/// Exports entity frequencies to a CSV file like `write_entity_frequencies_csv`.
+///
+/// # Arguments
+///
+/// * `frequencies` - The entity frequencies, e.g. from `entity_frequencies`.
+/// * `file_name` - A `&str` specifying the path of the CSV file to create.
+pub fn export_entity_frequencies_to_csv(frequencies: &[EntityFrequency], file_name: &str) -> io::Result<()> {
+    write_entity_frequencies_csv(frequencies, File::create(file_name)?)
+}
+
+/// Writes per-entry entities as CSV, one row per mention with the columns `index`, `entry_id`,
+/// `name`, `kind`, `source`, `text` and `offset`.
+///
+/// # Arguments
+///
+/// * `entries` - The per-entry entities, e.g. from `EntityRecognizer::recognize`.
+/// * `writer` - The destination implementing `Write`.
+pub fn write_entities_csv<W: Write>(entries: &[EntryEntities], writer: W) -> io::Result<()> {
+    let mut wtr = Writer::from_writer(writer);
+    wtr.write_record(["index", "entry_id", "name", "kind", "source", "text", "offset"])?;
+    for entry in entries {
+        for entity in &entry.entities {
+            wtr.write_record([
+                entry.index.to_string().as_str(),
+                &entry.entry_id.to_string(),
+                &entity.name,
+                entity.kind.as_str(),
+                entity.source.as_str(),
+                &entity.text,
+                &entity.offset.to_string(),
+            ])?;
+        }
+    }
+    wtr.flush()
+}
+
+/// Exports per-entry entities to a CSV file like `write_entities_csv`.
+///
+/// # Arguments
+///
+/// * `entries` - The per-entry entities, e.g. from `EntityRecognizer::recognize`.
+/// * `file_name` - A `&str` specifying the path of the CSV file to create.
+pub fn export_entities_to_csv(entries: &[EntryEntities], file_name: &str) -> io::Result<()> {
+    write_entities_csv(entries, File::create(file_name)?)
+}
+
+/// Normalizes a name the way texts are matched: lowercased words without apostrophe suffixes,
+/// joined by single spaces.
+fn name_key(name: &str) -> String {
+    Tokenizer::new().terms(name, &[TokenKind::Word, TokenKind::Number]).join(" ")
+}
+
+fn span_key(span: &[Word]) -> String {
+    span.iter().map(|word| word.token.text.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+fn is_all_caps(token: &Token) -> bool {
+    token.kind == TokenKind::Word && token.text.chars().filter(|c| c.is_alphabetic()).count() > 1 && !token.raw.chars().any(char::is_lowercase)
+}
+
+/// Number of capitalized words that directly follow each other from `position`.
+fn capitalized_run(words: &[Word], position: usize) -> usize {
+    words[position..]
+        .iter()
+        .enumerate()
+        .take_while(|(offset, word)| word.token.kind == TokenKind::Word && word.capitalized && (*offset == 0 || word.joined))
+        .count()
+}
+
+/// The words of a span as written, without the apostrophe suffix of the last one.
+fn written_name(span: &[Word]) -> String {
+    let words: Vec<&str> = span
+        .iter()
+        .map(|word| match word.token.suffix {
+            Some(_) => word.token.raw.rsplit_once(['\'', '\u{2019}']).map_or(word.token.raw.as_str(), |(stem, _)| stem),
+            None => word.token.raw.as_str(),
+        })
+        .collect();
+    words.join(" ")
+}
+
+fn mention(text: &str, span: &[Word], name: String, kind: EntityKind, source: EntitySource) -> EntityMention {
+    let (first, last) = (span[0].token, span[span.len() - 1].token);
+    EntityMention { name, kind, source, text: text[first.offset..last.offset + last.raw.len()].to_string(), offset: first.offset }
+}
@@ -0,0 +1,285 @@
+//! # RustySozluk N-grams
+//!
+//! Counts bigrams, trigrams and longer n-grams and ranks them as collocations. N-grams never
+//! span stopwords, numbers, URLs or clause boundaries, so `rust programlama dili` can be found
+//! but `ve rust` or `dili. bence` cannot.
+//!
+//! Besides raw frequency, collocations can be scored by pointwise mutual information, Dunning's
+//! log-likelihood ratio or the t-score. The result is a sorted `Vec<Ngram>` that can be used
+//! directly, printed with `print_ngrams` or written as CSV with `write_ngrams_csv`.
+
+use csv::Writer;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use crate::analyzer::stemmer::Stemmer;
+use crate::analyzer::tokenizer::{clause_ids, TokenKind, Tokenizer};
+use crate::parser::Entry;
+
+/// How n-grams are scored and ranked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum Association {
+    /// The number of occurrences.
+    #[default]
+    Frequency,
+    /// Pointwise mutual information: `log2(P(w1..wn) / (P(w1) * .. * P(wn)))`.
+    /// Favors rare words that always appear together.
+    Pmi,
+    /// Dunning's log-likelihood ratio of the first `n - 1` words against the last word.
+    /// Reliable for low counts and the usual choice for collocations.
+    LogLikelihood,
+    /// The t-score `(observed - expected) / sqrt(observed)`. Favors frequent collocations.
+    TScore,
+}
+
+/// An n-gram with its frequency and association score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Ngram {
+    /// The words of the n-gram, stemmed if a stemmer was set.
+    pub words: Vec<String>,
+    /// Number of occurrences.
+    pub frequency: usize,
+    /// Score under the chosen `Association`; equal to `frequency` for `Association::Frequency`.
+    pub score: f64,
+}
+
+impl Ngram {
+    /// Returns the words of the n-gram joined by spaces.
+    pub fn text(&self) -> String {
+        self.words.join(" ")
+    }
+}
+
+/// Extracts and ranks n-grams.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::ngrams::{Association, NgramExtractor};
+///
+/// let stopwords: HashSet<String> = ["bir", "ve"].iter().map(|s| s.to_string()).collect();
+/// let texts = ["rust programlama dili bir dil", "rust programlama dili ve go", "go programlama dili"];
+/// let ngrams = NgramExtractor::new(2).measure(Association::LogLikelihood).extract(texts, &stopwords);
+/// assert_eq!(ngrams[0].text(), "programlama dili");
+/// assert_eq!(ngrams[0].frequency, 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NgramExtractor {
+    n: usize,
+    min_frequency: usize,
+    measure: Association,
+    stemmer: Option<Stemmer>,
+}
+
+impl NgramExtractor {
+    /// Creates an extractor for n-grams of `n` words (at least 1), ranked by frequency,
+    /// keeping n-grams that occur at least twice.
+    pub fn new(n: usize) -> Self {
+        NgramExtractor {
+            n: n.max(1),
+            min_frequency: 2,
+            measure: Association::Frequency,
+            stemmer: None,
+        }
+    }
+
+    /// Sets the minimum number of occurrences an n-gram needs to be returned.
+    pub fn min_frequency(mut self, min_frequency: usize) -> Self {
+        self.min_frequency = min_frequency;
+        self
+    }
+
+    /// Sets the association measure used to score and rank n-grams.
+    pub fn measure(mut self, measure: Association) -> Self {
+        self.measure = measure;
+        self
+    }
+
+    /// Sets the stemmer applied to every word before counting; `None` (the default) counts words as written.
+    pub fn stemmer(mut self, stemmer: Option<Stemmer>) -> Self {
+        self.stemmer = stemmer;
+        self
+    }
+
+    /// Extracts n-grams from the contents of entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to analyze.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<Ngram>` sorted by descending score, then by descending frequency and text.
+    pub fn extract_entries(&self, entries: &[Entry], stopwords: &HashSet<String>) -> Vec<Ngram> {
+        self.extract(entries.iter().map(|entry| entry.content.as_str()), stopwords)
+    }
+
+    /// Extracts n-grams from texts.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - The texts to analyze.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<Ngram>` sorted by descending score, then by descending frequency and text.
+    pub fn extract<I, S>(&self, texts: I, stopwords: &HashSet<String>) -> Vec<Ngram>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        // counts[k - 1] holds the counts of k-grams; prefixes and single words are needed for scoring
+        let mut counts: Vec<HashMap<Vec<String>, usize>> = vec![HashMap::new(); self.n];
+        let mut total_words = 0;
+        for text in texts {
+            for segment in segments(text.as_ref(), stopwords, self.stemmer.as_ref()) {
+                total_words += segment.len();
+                for (k, table) in counts.iter_mut().enumerate() {
+                    for window in segment.windows(k + 1) {
+                        *table.entry(window.to_vec()).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let ngram_counts = &counts[self.n - 1];
+        let mut ngrams: Vec<Ngram> = ngram_counts
+            .iter()
+            .filter(|(_, &frequency)| frequency >= self.min_frequency.max(1))
+            .map(|(words, &frequency)| Ngram {
+                score: self.score(words, frequency, &counts, total_words),
+                words: words.clone(),
+                frequency,
+            })
+            .collect();
+        ngrams.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(b.frequency.cmp(&a.frequency))
+                .then_with(|| a.words.cmp(&b.words))
+        });
+        ngrams
+    }
+
+    fn score(&self, words: &[String], frequency: usize, counts: &[HashMap<Vec<String>, usize>], total_words: usize) -> f64 {
+        let total = total_words as f64;
+        let observed = frequency as f64;
+        let word_count = |word: &String| counts[0].get(std::slice::from_ref(word)).copied().unwrap_or(0) as f64;
+        match self.measure {
+            Association::Frequency => observed,
+            Association::Pmi => {
+                let independent: f64 = words.iter().map(|word| word_count(word) / total).product();
+                (observed / total / independent).log2()
+            }
+            Association::TScore => {
+                let expected = words.iter().map(word_count).product::<f64>() / total.powi(words.len() as i32 - 1);
+                (observed - expected) / observed.sqrt()
+            }
+            Association::LogLikelihood => {
+                let (prefix, last) = words.split_at(words.len() - 1);
+                let prefix_count = if prefix.is_empty() { total } else { counts[prefix.len() - 1].get(prefix).copied().unwrap_or(0) as f64 };
+                log_likelihood(observed, prefix_count, word_count(&last[0]), total)
+            }
+        }
+    }
+}
+
+/// Splits a text into runs of words that contain no stopword, non-word token or clause boundary.
+/// Words are stemmed when a stemmer is given.
+pub(crate) fn segments(text: &str, stopwords: &HashSet<String>, stemmer: Option<&Stemmer>) -> Vec<Vec<String>> {
+    let tokens = Tokenizer::new().tokenize(text);
+    let clauses = clause_ids(text, &tokens);
+    let mut segments = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut current_clause = 0;
+    for (token, clause) in tokens.into_iter().zip(clauses) {
+        let skipped = token.kind != TokenKind::Word || stopwords.contains(&token.text);
+        if skipped || clause != current_clause {
+            if !current.is_empty() {
+                segments.push(std::mem::take(&mut current));
+            }
+            current_clause = clause;
+            if skipped {
+                continue;
+            }
+        }
+        current.push(match stemmer {
+            Some(stemmer) => stemmer.stem(&token.text),
+            None => token.text,
+        });
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+/// Dunning's log-likelihood ratio for a 2x2 contingency table built from the joint count,
+/// the counts of both parts and the total.
+fn log_likelihood(joint: f64, first: f64, second: f64, total: f64) -> f64 {
+    let observed = [joint, first - joint, second - joint, total - first - second + joint];
+    let expected = [
+        first * second / total,
+        first * (total - second) / total,
+        (total - first) * second / total,
+        (total - first) * (total - second) / total,
+    ];
+    2.0 * observed
+        .iter()
+        .zip(expected)
+        .filter(|(&o, e)| o > 0.0 && *e > 0.0)
+        .map(|(&o, e)| o * (o / e).ln())
+        .sum::<f64>()
+}
+
+/// Prints the top n-grams as a table, like `print_top_words`.
+///
+/// # Arguments
+///
+/// * `ngrams` - The n-grams, usually as returned by `NgramExtractor::extract`.
+/// * `n` - The number of n-grams to print.
+pub fn print_ngrams(ngrams: &[Ngram], n: usize) {
+    let width = ngrams.iter().take(n).map(|ngram| ngram.text().chars().count()).max().unwrap_or(0).max(15);
+    println!("Top {} n-grams:", n);
+    println!("{:<10} {:<width$} {:<10} Score", "Rank", "N-gram", "Frequency", width = width);
+    println!("{}", "-".repeat(width + 28));
+    for (idx, ngram) in ngrams.iter().enumerate().take(n) {
+        println!("{:<10} {:<width$} {:<10} {:.4}", idx + 1, ngram.text(), ngram.frequency, ngram.score, width = width);
+    }
+}
+
+/// Writes n-grams as CSV with the columns `Rank`, `Ngram`, `Frequency` and `Score`.
+///
+/// # Arguments
+///
+/// * `ngrams` - The n-grams to write.
+/// * `writer` - The destination implementing `Write`.
+///
+/// # Returns
+///
+/// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+pub fn write_ngrams_csv<W: Write>(ngrams: &[Ngram], writer: W) -> io::Result<()> {
+    let mut wtr = Writer::from_writer(writer);
+    wtr.write_record(["Rank", "Ngram", "Frequency", "Score"])?;
+    for (idx, ngram) in ngrams.iter().enumerate() {
+        wtr.write_record(&[(idx + 1).to_string(), ngram.text(), ngram.frequency.to_string(), ngram.score.to_string()])?;
+    }
+    wtr.flush()
+}
+
+/// Writes n-grams to a CSV file like `write_ngrams_csv`.
+///
+/// # Arguments
+///
+/// * `ngrams` - The n-grams to write.
+/// * `file_name` - The path of the CSV file to create.
+///
+/// # Returns
+///
+/// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+pub fn export_ngrams_to_csv(ngrams: &[Ngram], file_name: &str) -> io::Result<()> {
+    write_ngrams_csv(ngrams, std::fs::File::create(file_name)?)
+}
@@ -0,0 +1,285 @@
+//! # RustySozluk Readability
+//!
+//! Text statistics and Turkish readability formulas for entries and collections of entries.
+//!
+//! Every Turkish syllable is built around exactly one vowel, so syllables are counted as vowels;
+//! words without vowels such as `tbmm` are read letter by letter (`te-be-me-me`) and count one
+//! syllable per letter. Two formulas are computed from the counts:
+//!
+//! * **Ateşman** (1997): `198.825 - 40.175 × syllables per word - 2.610 × words per sentence`.
+//!   Scores from about 90 up are very easy, below 30 very difficult.
+//! * **Bezirci–Yılmaz** (2010): `sqrt(words per sentence × (0.84 × H3 + 1.5 × H4 + 3.5 × H5 + 26.25 × H6))`,
+//!   where `Hn` is the number of words with `n` syllables (6 and more for `H6`) per sentence. The
+//!   score approximates the years of education needed to read the text.
+
+use csv::Writer;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+use crate::analyzer::tokenizer::{TokenKind, Tokenizer};
+use crate::parser::Entry;
+
+const VOWELS: &str = "aeıioöuüâîûAEIİOÖUÜÂÎÛ";
+
+/// Counts the syllables of a Turkish word.
+///
+/// # Arguments
+///
+/// * `word` - The word, in any case.
+///
+/// # Returns
+///
+/// The number of vowels, or the number of letters if the word has no vowel.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::readability::syllables;
+///
+/// assert_eq!(syllables("kitaplarımızdan"), 6);
+/// assert_eq!(syllables("Türkiye"), 3);
+/// assert_eq!(syllables("tbmm"), 4);
+/// ```
+pub fn syllables(word: &str) -> usize {
+    match word.chars().filter(|&c| VOWELS.contains(c)).count() {
+        0 => word.chars().filter(|c| c.is_alphabetic()).count(),
+        vowels => vowels,
+    }
+}
+
+/// Readability bands of the Ateşman score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ReadingLevel {
+    /// 90 and above.
+    VeryEasy,
+    /// 70 to 89.
+    Easy,
+    /// 50 to 69.
+    Medium,
+    /// 30 to 49.
+    Difficult,
+    /// Below 30.
+    VeryDifficult,
+}
+
+/// Counts of a text or a collection of texts, with the statistics derived from them.
+///
+/// Statistics of several texts can be added together (`+`, `+=` or `sum`) to get the statistics
+/// of the whole collection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TextStatistics {
+    /// Number of texts the counts are taken from.
+    pub texts: usize,
+    /// Characters, including spaces and punctuation.
+    pub characters: usize,
+    /// Characters of the words.
+    pub letters: usize,
+    /// Words, not counting numbers, links, hashtags, mentions and emoji.
+    pub words: usize,
+    /// Sentences, ended by `.`, `!`, `?`, `…` or a line break.
+    pub sentences: usize,
+    /// Syllables of the words.
+    pub syllables: usize,
+    /// Words with 3, 4, 5 and 6 or more syllables.
+    pub polysyllables: [usize; 4],
+}
+
+impl TextStatistics {
+    /// Computes the statistics of a text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to analyze.
+    ///
+    /// # Returns
+    ///
+    /// The `TextStatistics` of the text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustysozluk::analyzer::readability::TextStatistics;
+    ///
+    /// let stats = TextStatistics::from_text("Bugün hava çok güzel. Sahilde yürüyüş yaptık, 3.5 saat sürdü!");
+    /// assert_eq!(stats.sentences, 2);
+    /// assert_eq!(stats.words, 9);
+    /// assert!(stats.atesman().unwrap() > 70.0);
+    /// ```
+    pub fn from_text(text: &str) -> Self {
+        let mut stats = TextStatistics { texts: 1, characters: text.chars().count(), ..Default::default() };
+        let mut previous_end = 0;
+        let mut open_sentence = false;
+        for token in Tokenizer::new().lowercase(false).tokenize(text) {
+            let gap = text.get(previous_end..token.offset).unwrap_or("");
+            if open_sentence && gap.contains(['.', '!', '?', '…', '\n']) {
+                stats.sentences += 1;
+                open_sentence = false;
+            }
+            previous_end = token.offset + token.raw.len();
+            if token.kind != TokenKind::Word {
+                continue;
+            }
+            let count = syllables(&token.raw);
+            stats.words += 1;
+            stats.letters += token.raw.chars().filter(|c| c.is_alphanumeric()).count();
+            stats.syllables += count;
+            if count >= 3 {
+                stats.polysyllables[count.min(6) - 3] += 1;
+            }
+            open_sentence = true;
+        }
+        stats.sentences += usize::from(open_sentence);
+        stats
+    }
+
+    /// Computes the statistics of an entry.
+    pub fn from_entry(entry: &Entry) -> Self {
+        TextStatistics::from_text(&entry.content)
+    }
+
+    /// Computes the combined statistics of a collection of entries.
+    pub fn from_entries(entries: &[Entry]) -> Self {
+        entries.iter().map(TextStatistics::from_entry).sum()
+    }
+
+    /// Average number of letters per word.
+    pub fn mean_word_length(&self) -> f64 {
+        ratio(self.letters, self.words)
+    }
+
+    /// Average number of words per sentence.
+    pub fn mean_sentence_length(&self) -> f64 {
+        ratio(self.words, self.sentences)
+    }
+
+    /// Average number of syllables per word.
+    pub fn syllables_per_word(&self) -> f64 {
+        ratio(self.syllables, self.words)
+    }
+
+    /// Returns the Ateşman readability score, higher is easier; `None` for a text without words.
+    /// The score is not clamped, so very short sentences of short words can exceed 100.
+    pub fn atesman(&self) -> Option<f64> {
+        (self.words > 0).then(|| 198.825 - 40.175 * self.syllables_per_word() - 2.610 * self.mean_sentence_length())
+    }
+
+    /// Returns the band of the Ateşman score; `None` for a text without words.
+    pub fn reading_level(&self) -> Option<ReadingLevel> {
+        self.atesman().map(|score| match score {
+            score if score >= 90.0 => ReadingLevel::VeryEasy,
+            score if score >= 70.0 => ReadingLevel::Easy,
+            score if score >= 50.0 => ReadingLevel::Medium,
+            score if score >= 30.0 => ReadingLevel::Difficult,
+            _ => ReadingLevel::VeryDifficult,
+        })
+    }
+
+    /// Returns the Bezirci–Yılmaz readability score, roughly the years of education needed to
+    /// read the text; `None` for a text without words.
+    pub fn bezirci_yilmaz(&self) -> Option<f64> {
+        if self.words == 0 {
+            return None;
+        }
+        let per_sentence = |count: usize| ratio(count, self.sentences);
+        let [h3, h4, h5, h6] = self.polysyllables.map(per_sentence);
+        Some((self.mean_sentence_length() * (0.84 * h3 + 1.5 * h4 + 3.5 * h5 + 26.25 * h6)).sqrt())
+    }
+}
+
+impl AddAssign for TextStatistics {
+    fn add_assign(&mut self, other: TextStatistics) {
+        self.texts += other.texts;
+        self.characters += other.characters;
+        self.letters += other.letters;
+        self.words += other.words;
+        self.sentences += other.sentences;
+        self.syllables += other.syllables;
+        for (count, other) in self.polysyllables.iter_mut().zip(other.polysyllables) {
+            *count += other;
+        }
+    }
+}
+
+impl Add for TextStatistics {
+    type Output = TextStatistics;
+
+    fn add(mut self, other: TextStatistics) -> TextStatistics {
+        self += other;
+        self
+    }
+}
+
+impl Sum for TextStatistics {
+    fn sum<I: Iterator<Item = TextStatistics>>(iter: I) -> Self {
+        iter.fold(TextStatistics::default(), Add::add)
+    }
+}
+
+/// Computes the combined statistics of the entries of every title.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` from one or more titles.
+///
+/// # Returns
+///
+/// A `Vec<(String, TextStatistics)>` with one row per title, sorted by title.
+pub fn statistics_by_title(entries: &[Entry]) -> Vec<(String, TextStatistics)> {
+    let mut titles: BTreeMap<&str, TextStatistics> = BTreeMap::new();
+    for entry in entries {
+        *titles.entry(entry.title.as_str()).or_default() += TextStatistics::from_entry(entry);
+    }
+    titles.into_iter().map(|(title, stats)| (title.to_string(), stats)).collect()
+}
+
+/// Writes named statistics as CSV, one row per name with the counts and the derived statistics.
+///
+/// # Arguments
+///
+/// * `rows` - The statistics, e.g. from `statistics_by_title`.
+/// * `writer` - The destination implementing `Write`.
+pub fn write_statistics_csv<W: Write>(rows: &[(String, TextStatistics)], writer: W) -> io::Result<()> {
+    let mut wtr = Writer::from_writer(writer);
+    wtr.write_record([
+        "name", "texts", "characters", "words", "sentences", "syllables",
+        "mean_word_length", "mean_sentence_length", "syllables_per_word", "atesman", "bezirci_yilmaz",
+    ])?;
+    let optional = |value: Option<f64>| value.map(|value| format!("{:.2}", value)).unwrap_or_default();
+    for (name, stats) in rows {
+        wtr.write_record([
+            name.clone(),
+            stats.texts.to_string(),
+            stats.characters.to_string(),
+            stats.words.to_string(),
+            stats.sentences.to_string(),
+            stats.syllables.to_string(),
+            format!("{:.2}", stats.mean_word_length()),
+            format!("{:.2}", stats.mean_sentence_length()),
+            format!("{:.2}", stats.syllables_per_word()),
+            optional(stats.atesman()),
+            optional(stats.bezirci_yilmaz()),
+        ])?;
+    }
+    wtr.flush()
+}
+
+/// Exports named statistics to a CSV file like `write_statistics_csv`.
+///
+/// # Arguments
+///
+/// * `rows` - The statistics, e.g. from `statistics_by_title`.
+/// * `file_name` - A `&str` specifying the path of the CSV file to create.
+pub fn export_statistics_to_csv(rows: &[(String, TextStatistics)], file_name: &str) -> io::Result<()> {
+    write_statistics_csv(rows, File::create(file_name)?)
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
@@ -0,0 +1,53 @@
+//! # RustySozluk Bundled Resources
+//!
+//! The Turkish stopword list, the SWNetTR sentiment lexicon, the lexicon of emoticons, emoji
+//! and slang, and the gazetteer of named entities from the `files/` folder are
+//! embedded into the library (gzip-compressed) when the default `embedded-resources` feature is
+//! enabled, so the analyzer works without any files next to the program. They are decompressed
+//! and parsed the first time they are used and then kept for the lifetime of the program.
+//!
+//! To use custom resources, load them with `read_stopwords`, `read_sentiment_lexicon`,
+//! `read_expression_lexicon` and `read_gazetteer` (or their `_from` variants) instead.
+
+use flate2::read::GzDecoder;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use crate::analyzer::analyzer::{read_sentiment_lexicon_from, read_stopwords_from, Sentiment};
+use crate::analyzer::ner::{read_gazetteer_from, Gazetteer};
+use crate::analyzer::sentiment::{read_expression_lexicon_from, Expression};
+
+static STOPWORDS_GZ: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/stopwords.csv.gz"));
+static LEXICON_GZ: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/SWNetTR.csv.gz"));
+static EXPRESSIONS_GZ: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/expressions.csv.gz"));
+static GAZETTEER_GZ: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/gazetteer.csv.gz"));
+
+/// Returns the bundled Turkish stopwords.
+pub fn default_stopwords() -> &'static HashSet<String> {
+    static STOPWORDS: OnceLock<HashSet<String>> = OnceLock::new();
+    STOPWORDS.get_or_init(|| read_stopwords_from(GzDecoder::new(STOPWORDS_GZ)).expect("bundled stopwords are valid"))
+}
+
+/// Returns the bundled SWNetTR sentiment lexicon.
+pub fn default_lexicon() -> &'static HashMap<String, Sentiment> {
+    static LEXICON: OnceLock<HashMap<String, Sentiment>> = OnceLock::new();
+    LEXICON.get_or_init(|| read_sentiment_lexicon_from(GzDecoder::new(LEXICON_GZ)).expect("bundled lexicon is valid"))
+}
+
+/// Returns the words of the bundled sentiment lexicon and stopwords, the dictionary of
+/// `Stemmer::embedded`.
+pub fn default_dictionary() -> &'static HashSet<String> {
+    static DICTIONARY: OnceLock<HashSet<String>> = OnceLock::new();
+    DICTIONARY.get_or_init(|| default_lexicon().keys().chain(default_stopwords()).cloned().collect())
+}
+
+/// Returns the bundled lexicon of emoticons, emoji, slang and irony markers.
+pub fn default_expressions() -> &'static HashMap<String, Expression> {
+    static EXPRESSIONS: OnceLock<HashMap<String, Expression>> = OnceLock::new();
+    EXPRESSIONS.get_or_init(|| read_expression_lexicon_from(GzDecoder::new(EXPRESSIONS_GZ)).expect("bundled expressions are valid"))
+}
+
+/// Returns the bundled gazetteer of Turkish provinces, places, politicians and organizations.
+pub fn default_gazetteer() -> &'static Gazetteer {
+    static GAZETTEER: OnceLock<Gazetteer> = OnceLock::new();
+    GAZETTEER.get_or_init(|| read_gazetteer_from(GzDecoder::new(GAZETTEER_GZ)).expect("bundled gazetteer is valid"))
+}
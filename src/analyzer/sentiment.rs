@@ -0,0 +1,777 @@
+//! # RustySozluk Sentiment
+//!
+//! `SentimentAnalyzer` scores entries against a sentiment lexicon. It is built once from a
+//! lexicon and a stopword set, given as file paths, readers or in-memory collections (or the
+//! bundled ones with `SentimentAnalyzer::embedded`), and can then be reused for any number of
+//! calls without touching the disk again.
+//!
+//! Scoring goes beyond single-word lookups: multi-word lexicon entries (`a vitamini`) are
+//! matched as phrases, negation (`güzel değil`, `sorun yok`, `sevmedim`) flips the tone of a
+//! word and intensifiers (`çok`, `aşırı`) scale it. `ScoringRules` configures all three.
+//!
+//! A second, smaller lexicon covers what Sözlük writing adds on top of dictionary words:
+//! emoticons (`:)`, `:(`), emoji, slang (`cillop`, `gıcık`) and irony markers. Its entries are
+//! weighted per kind, and an irony marker such as `(!)` turns the words of its clause around,
+//! so `harika bir yönetim(!)` counts as negative. Elongated words (`güzeeeel`, `xDDD`) are looked
+//! up in their plain form. The bundled list can be extended from a CSV with `read_expression_lexicon`.
+//!
+//! The analysis returns a `SentimentReport` with per-entry scores and the words that matched
+//! the lexicon, plus corpus-wide aggregates. Reports can be serialized (e.g. to JSON) and are
+//! printed as a table through their `Display` implementation.
+
+use csv::Reader;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Read};
+use std::path::Path;
+use std::fs::File;
+use std::sync::OnceLock;
+use crate::analyzer::analyzer::{lookup_sentiment, read_sentiment_lexicon_from, read_stopwords_from, Sentiment};
+#[cfg(feature = "embedded-resources")]
+use crate::analyzer::resources::{default_expressions, default_lexicon, default_stopwords};
+use crate::analyzer::stemmer::Stemmer;
+use crate::analyzer::tokenizer::{clause_ids, normalize_emoticon, turkish_lowercase, Token, TokenKind, Tokenizer};
+use crate::parser::Entry;
+
+/// Words that negate the sentiment word before them.
+const NEGATORS: &[&str] = &[
+    "değil", "değildi", "değildir", "değilim", "değiliz", "değilsin", "değilsiniz", "değiller", "değilmiş",
+    "yok", "yoktu", "yoktur", "yokmuş",
+];
+
+/// Token kinds scored by `SentimentAnalyzer`: `CONTENT_TOKEN_KINDS` plus emoticons.
+const SCORED_TOKEN_KINDS: [TokenKind; 5] = [TokenKind::Word, TokenKind::Hashtag, TokenKind::Mention, TokenKind::Emoji, TokenKind::Emoticon];
+
+/// Stopwords that carry sentiment of their own and are scored despite being in the stopword list.
+const SENTIMENT_STOPWORDS: &[&str] = &["iyi", "güzel", "kötü"];
+
+/// Words that scale the tone of the sentiment word after them, with their factors.
+const INTENSIFIERS: &[(&str, f32)] = &[
+    ("aşırı", 2.0), ("çok", 1.5), ("en", 1.5), ("acayip", 1.5),
+    ("fazla", 1.3), ("oldukça", 1.3), ("gayet", 1.3), ("epey", 1.3), ("bayağı", 1.3), ("baya", 1.3),
+    ("gerçekten", 1.3), ("cidden", 1.3), ("resmen", 1.3),
+    ("biraz", 0.5), ("az", 0.5), ("azıcık", 0.5),
+];
+
+/// Beginnings of negative verb suffixes (`sev-medim`, `sev-miyorum`, `gel-mez`).
+/// The bare `-me/-ma` is left out because it also forms nouns (`sevme`, `sevmek`).
+const NEGATIVE_VERB_SUFFIXES: &[&str] = &[
+    "madı", "medi", "mamış", "memiş", "mayacak", "meyecek", "mayan", "meyen", "madan", "meden",
+    "mıyor", "miyor", "muyor", "müyor", "maz", "mez", "mam", "mem", "mayın", "meyin",
+];
+
+/// The kinds of entries in an expression lexicon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpressionKind {
+    /// An ASCII emoticon such as `:)` or `<3`.
+    Emoticon,
+    /// An emoji such as 👍 or 😡.
+    Emoji,
+    /// A slang word such as `cillop` or `gıcık`, negated and intensified like a lexicon word.
+    Slang,
+    /// An irony marker such as `(!)`, whose tone is a factor applied to the words of its clause.
+    Irony,
+}
+
+/// An entry of an expression lexicon.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Expression {
+    /// What kind of expression it is.
+    pub kind: ExpressionKind,
+    /// Tone of the expression, or the factor applied to the clause for irony markers.
+    pub tone: f32,
+}
+
+/// Reads an expression lexicon from a CSV file.
+///
+/// The file has an `EXPRESSION,KIND,TONE` header, where `KIND` is `emoticon`, `emoji`, `slang`
+/// or `irony`. Emoticons are stored without nose and repeated mouths (`:-))` as `:)`), emoji
+/// without variation selectors and skin tones, and slang in lowercase, so each entry matches
+/// every way of writing it.
+///
+/// # Arguments
+///
+/// * `file_path` - Path to the CSV file.
+///
+/// # Returns
+///
+/// A `HashMap<String, Expression>` to pass to `SentimentAnalyzer::expressions`.
+pub fn read_expression_lexicon<P: AsRef<Path>>(file_path: P) -> io::Result<HashMap<String, Expression>> {
+    read_expression_lexicon_from(File::open(file_path)?)
+}
+
+/// Reads an expression lexicon from any reader, in the same format as `read_expression_lexicon`.
+///
+/// # Arguments
+///
+/// * `reader` - The source implementing `Read`.
+///
+/// # Returns
+///
+/// A `HashMap<String, Expression>` to pass to `SentimentAnalyzer::expressions`.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::sentiment::{read_expression_lexicon_from, ExpressionKind};
+///
+/// let csv = "EXPRESSION,KIND,TONE\n:-)),emoticon,0.25\nCillop,slang,0.2\n";
+/// let expressions = read_expression_lexicon_from(csv.as_bytes()).unwrap();
+/// assert_eq!(expressions[":)"].kind, ExpressionKind::Emoticon);
+/// assert!(expressions.contains_key("cillop"));
+/// ```
+pub fn read_expression_lexicon_from<R: Read>(reader: R) -> io::Result<HashMap<String, Expression>> {
+    let mut expressions = HashMap::new();
+    let mut rdr = Reader::from_reader(reader);
+    for result in rdr.records() {
+        let record = result?;
+        let (text, kind) = (record[0].trim(), record[1].trim().to_lowercase());
+        let kind = match kind.as_str() {
+            "emoticon" => ExpressionKind::Emoticon,
+            "emoji" => ExpressionKind::Emoji,
+            "slang" => ExpressionKind::Slang,
+            "irony" => ExpressionKind::Irony,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unknown expression kind `{}`", &record[1]))),
+        };
+        let tone: f32 = record[2].trim().replace(',', ".").parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let key = match kind {
+            ExpressionKind::Emoticon | ExpressionKind::Irony => normalize_emoticon(text),
+            ExpressionKind::Emoji => strip_emoji_modifiers(text),
+            ExpressionKind::Slang => turkish_lowercase(text),
+        };
+        expressions.insert(key, Expression { kind, tone });
+    }
+    Ok(expressions)
+}
+
+/// Drops variation selectors and skin tones, so `👍🏽` and `❤️` match `👍` and `❤`.
+fn strip_emoji_modifiers(text: &str) -> String {
+    text.chars().filter(|&c| !is_emoji_modifier(c)).collect()
+}
+
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c, '\u{FE0E}' | '\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}')
+}
+
+/// Rules for negation, intensifiers, multi-word expressions, emoticons, emoji, slang and irony
+/// used by `SentimentAnalyzer`.
+///
+/// The default rules know the common Turkish negators and intensifiers; `ScoringRules::none`
+/// turns everything off, which scores every word on its own.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::sentiment::ScoringRules;
+///
+/// let mut rules = ScoringRules::default();
+/// rules.intensifiers.insert("feci".to_string(), 1.8);
+/// rules.negation_window = 2;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoringRules {
+    /// Words that negate the sentiment word before them (`güzel değil`, `sorun yok`).
+    pub negators: HashSet<String>,
+    /// How many words after a sentiment word a negator may appear, within the same clause.
+    pub negation_window: usize,
+    /// Factor applied to the tone of a negated word. Negative factors also flip its polarity.
+    pub negation_factor: f32,
+    /// Whether negative verb forms (`sevmedim`, `sevmiyorum`) negate the verb they are built on.
+    pub verb_negation: bool,
+    /// Words that scale the tone of the sentiment word after them, with their factors.
+    /// Factors above `1` intensify (`çok`, `aşırı`), factors below `1` soften (`biraz`).
+    pub intensifiers: HashMap<String, f32>,
+    /// How many words before a sentiment word an intensifier may appear, within the same clause.
+    pub intensifier_window: usize,
+    /// Whether multi-word lexicon entries (`a vitamini`) are matched as phrases.
+    pub phrases: bool,
+    /// Factor applied to the tone of emoticons from the expression lexicon.
+    pub emoticon_weight: f32,
+    /// Factor applied to the tone of emoji from the expression lexicon.
+    pub emoji_weight: f32,
+    /// Factor applied to the tone of slang words from the expression lexicon.
+    pub slang_weight: f32,
+    /// Whether irony markers (`(!)`) apply their factor to the words before them in the same clause.
+    pub irony: bool,
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        ScoringRules {
+            negators: NEGATORS.iter().map(|word| word.to_string()).collect(),
+            negation_window: 3,
+            negation_factor: -1.0,
+            verb_negation: true,
+            intensifiers: INTENSIFIERS.iter().map(|(word, factor)| (word.to_string(), *factor)).collect(),
+            intensifier_window: 2,
+            phrases: true,
+            emoticon_weight: 1.0,
+            emoji_weight: 1.0,
+            slang_weight: 1.0,
+            irony: true,
+        }
+    }
+}
+
+impl ScoringRules {
+    /// Rules that score every word on its own, without negation, intensifiers, phrases or irony.
+    pub fn none() -> Self {
+        ScoringRules {
+            negators: HashSet::new(),
+            negation_window: 0,
+            negation_factor: 1.0,
+            verb_negation: false,
+            intensifiers: HashMap::new(),
+            intensifier_window: 0,
+            phrases: false,
+            emoticon_weight: 1.0,
+            emoji_weight: 1.0,
+            slang_weight: 1.0,
+            irony: false,
+        }
+    }
+}
+
+/// Scores entries against a sentiment lexicon.
+///
+/// # Example
+///
+/// ```no_run
+/// use rustysozluk::analyzer::sentiment::SentimentAnalyzer;
+/// use rustysozluk::Entry;
+///
+/// let analyzer = SentimentAnalyzer::from_files("my/SWNetTR.csv", "my/stopwords.csv").unwrap();
+/// let entries = vec![Entry { content: "harika bir film".to_string(), ..Default::default() }];
+/// let report = analyzer.analyze(&entries);
+/// println!("{}", report);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SentimentAnalyzer {
+    lexicon: Cow<'static, HashMap<String, Sentiment>>,
+    stopwords: Cow<'static, HashSet<String>>,
+    expressions: Cow<'static, HashMap<String, Expression>>,
+    stemmer: Option<Stemmer>,
+    rules: ScoringRules,
+    phrase_index: OnceLock<PhraseIndex>,
+}
+
+/// First words and maximum length of the multi-word lexicon entries, built on first use.
+#[derive(Debug, Clone, Default)]
+struct PhraseIndex {
+    first_words: HashSet<String>,
+    max_words: usize,
+}
+
+/// A lexicon entry found at some position of a text.
+struct LexiconMatch<'a> {
+    words: usize,
+    key: &'a str,
+    sentiment: Sentiment,
+    negative_verb: bool,
+    kind: Option<ExpressionKind>,
+}
+
+impl SentimentAnalyzer {
+    /// Creates an analyzer from an in-memory lexicon and stopword set, without an expression lexicon.
+    /// Stemmed lexicon lookups and the default `ScoringRules` are enabled; see `stemmer` and `rules` to change that.
+    /// The stemmer uses the lexicon words as its dictionary, so a word is only scored through a stem
+    /// that is itself in the lexicon.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::{HashMap, HashSet};
+    /// use rustysozluk::analyzer::analyzer::Sentiment;
+    /// use rustysozluk::analyzer::sentiment::SentimentAnalyzer;
+    ///
+    /// let mut lexicon = HashMap::new();
+    /// lexicon.insert("kitap".to_string(), Sentiment { tone: 0.1, polarity: 1 });
+    /// lexicon.insert("anlat".to_string(), Sentiment { tone: -0.2, polarity: -1 });
+    /// let analyzer = SentimentAnalyzer::new(lexicon, HashSet::new());
+    ///
+    /// assert_eq!(analyzer.score_text("kitapları").matched[0].lexicon_word, "kitap");
+    /// // `anla-dım` has no lexicon entry and is not mistaken for `anlat`
+    /// assert!(analyzer.score_text("anladım").matched.is_empty());
+    /// ```
+    pub fn new(lexicon: HashMap<String, Sentiment>, stopwords: HashSet<String>) -> Self {
+        let stemmer = Stemmer::with_dictionary(lexicon.keys().cloned());
+        SentimentAnalyzer {
+            lexicon: Cow::Owned(lexicon),
+            stopwords: Cow::Owned(stopwords),
+            expressions: Cow::Owned(HashMap::new()),
+            stemmer: Some(stemmer),
+            rules: ScoringRules::default(),
+            phrase_index: OnceLock::new(),
+        }
+    }
+
+    /// Creates an analyzer that uses the bundled SWNetTR lexicon, Turkish stopwords and expression lexicon.
+    /// The bundled resources are shared, not copied, so this is cheap to call repeatedly.
+    #[cfg(feature = "embedded-resources")]
+    pub fn embedded() -> Self {
+        SentimentAnalyzer {
+            lexicon: Cow::Borrowed(default_lexicon()),
+            stopwords: Cow::Borrowed(default_stopwords()),
+            expressions: Cow::Borrowed(default_expressions()),
+            stemmer: Some(Stemmer::embedded()),
+            rules: ScoringRules::default(),
+            phrase_index: OnceLock::new(),
+        }
+    }
+
+    /// Creates an analyzer by reading the lexicon and stopwords from files.
+    ///
+    /// # Arguments
+    ///
+    /// * `lexicon_path` - Path to a lexicon CSV in the `ID,WORD,TONE,POLARITY` format of SWNetTR.
+    /// * `stopwords_path` - Path to a stopwords file with one word per line.
+    pub fn from_files<L: AsRef<Path>, S: AsRef<Path>>(lexicon_path: L, stopwords_path: S) -> io::Result<Self> {
+        Self::from_readers(File::open(lexicon_path)?, File::open(stopwords_path)?)
+    }
+
+    /// Creates an analyzer by reading the lexicon and stopwords from any readers.
+    ///
+    /// # Arguments
+    ///
+    /// * `lexicon` - A reader providing a lexicon CSV in the `ID,WORD,TONE,POLARITY` format.
+    /// * `stopwords` - A reader providing one stopword per line.
+    pub fn from_readers<L: Read, S: Read>(lexicon: L, stopwords: S) -> io::Result<Self> {
+        Ok(Self::new(read_sentiment_lexicon_from(lexicon)?, read_stopwords_from(stopwords)?))
+    }
+
+    /// Sets the lexicon of emoticons, emoji, slang and irony markers, replacing the current one.
+    /// To extend the bundled lexicon, start from a copy of `expression_lexicon()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::{HashMap, HashSet};
+    /// use rustysozluk::analyzer::sentiment::{Expression, ExpressionKind, SentimentAnalyzer};
+    ///
+    /// let mut expressions = HashMap::new();
+    /// expressions.insert("cillop".to_string(), Expression { kind: ExpressionKind::Slang, tone: 0.2 });
+    /// expressions.insert(":(".to_string(), Expression { kind: ExpressionKind::Emoticon, tone: -0.25 });
+    /// expressions.insert("(!)".to_string(), Expression { kind: ExpressionKind::Irony, tone: -1.0 });
+    /// let analyzer = SentimentAnalyzer::new(HashMap::new(), HashSet::new()).expressions(expressions);
+    ///
+    /// assert!(analyzer.score_text("cilloppp bir araba").tone > 0.0);
+    /// assert!(analyzer.score_text("cillop(!) bir yönetim").tone < 0.0);
+    /// assert!(analyzer.score_text("kaybettik :-((").tone < 0.0);
+    /// ```
+    pub fn expressions(mut self, expressions: HashMap<String, Expression>) -> Self {
+        self.expressions = Cow::Owned(expressions);
+        self
+    }
+
+    /// Sets the stemmer used when a word is not found in the lexicon as written; `None` disables stemming.
+    pub fn stemmer(mut self, stemmer: Option<Stemmer>) -> Self {
+        self.stemmer = stemmer;
+        self
+    }
+
+    /// Sets the negation, intensifier and phrase rules; `ScoringRules::none()` scores words on their own.
+    pub fn rules(mut self, rules: ScoringRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Returns the scoring rules used by the analyzer.
+    pub fn scoring_rules(&self) -> &ScoringRules {
+        &self.rules
+    }
+
+    /// Returns the lexicon used by the analyzer.
+    pub fn lexicon(&self) -> &HashMap<String, Sentiment> {
+        &self.lexicon
+    }
+
+    /// Returns the lexicon of emoticons, emoji, slang and irony markers used by the analyzer.
+    pub fn expression_lexicon(&self) -> &HashMap<String, Expression> {
+        &self.expressions
+    }
+
+    /// Returns the stopwords used by the analyzer.
+    pub fn stopwords(&self) -> &HashSet<String> {
+        &self.stopwords
+    }
+
+    /// Scores a list of entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to analyze.
+    ///
+    /// # Returns
+    ///
+    /// A `SentimentReport` with one `EntrySentiment` per entry.
+    pub fn analyze(&self, entries: &[Entry]) -> SentimentReport {
+        let scores = entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let mut score = self.score_text(&entry.content);
+                score.index = index;
+                score.entry_id = entry.id;
+                score
+            })
+            .collect();
+        SentimentReport::from_entries(scores)
+    }
+
+    /// Scores a single piece of text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string slice containing the text to score.
+    ///
+    /// # Returns
+    ///
+    /// An `EntrySentiment` with `index` and `entry_id` set to `0`.
+    pub fn score_text(&self, text: &str) -> EntrySentiment {
+        let tokens: Vec<Token> = Tokenizer::new()
+            .tokenize(text)
+            .into_iter()
+            .filter(|token| SCORED_TOKEN_KINDS.contains(&token.kind))
+            .collect();
+        let clauses = clause_ids(text, &tokens);
+        let words: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        let counted = words.iter().filter(|word| !self.is_stopword(word)).count();
+
+        let mut matched: Vec<MatchedWord> = Vec::new();
+        let mut matched_clauses = Vec::new();
+        let mut position = 0;
+        while position < words.len() {
+            let clause_end = clauses.partition_point(|&clause| clause <= clauses[position]);
+            let Some(found) = self.match_at(&words[..clause_end], position) else {
+                position += 1;
+                continue;
+            };
+            if found.kind == Some(ExpressionKind::Irony) {
+                let factor = found.sentiment.tone;
+                for (word, _) in matched.iter_mut().zip(&matched_clauses).rev().take_while(|(_, &clause)| clause == clauses[position]) {
+                    word.tone *= factor;
+                    if factor < 0.0 {
+                        word.polarity = -word.polarity;
+                    }
+                    word.ironic = true;
+                }
+                position += 1;
+                continue;
+            }
+            let end = position + found.words;
+            let weight = match found.kind {
+                Some(ExpressionKind::Emoticon) => self.rules.emoticon_weight,
+                Some(ExpressionKind::Emoji) => self.rules.emoji_weight,
+                Some(ExpressionKind::Slang) => self.rules.slang_weight,
+                Some(ExpressionKind::Irony) | None => 1.0,
+            };
+            if matches!(found.kind, Some(ExpressionKind::Emoticon | ExpressionKind::Emoji)) {
+                matched.push(MatchedWord {
+                    word: words[position].to_string(),
+                    lexicon_word: found.key.to_string(),
+                    tone: found.sentiment.tone * weight,
+                    polarity: found.sentiment.polarity,
+                    negated: false,
+                    intensity: 1.0,
+                    expression: found.kind,
+                    ironic: false,
+                });
+                matched_clauses.push(clauses[position]);
+                position = end;
+                continue;
+            }
+            let intensity: f32 = words[position.saturating_sub(self.rules.intensifier_window)..position]
+                .iter()
+                .zip(&clauses[position.saturating_sub(self.rules.intensifier_window)..position])
+                .filter(|(_, &clause)| clause == clauses[position])
+                .filter_map(|(word, _)| self.rules.intensifiers.get(*word))
+                .product();
+            let negator_follows = words[end..clause_end.min(end + self.rules.negation_window)]
+                .iter()
+                .any(|word| self.rules.negators.contains(*word));
+            let negated = negator_follows != found.negative_verb;
+            let (mut tone, mut polarity) = (found.sentiment.tone * weight * intensity, found.sentiment.polarity);
+            if negated {
+                tone *= self.rules.negation_factor;
+                if self.rules.negation_factor < 0.0 {
+                    polarity = -polarity;
+                }
+            }
+            matched.push(MatchedWord {
+                word: words[position..end].join(" "),
+                lexicon_word: found.key.to_string(),
+                tone,
+                polarity,
+                negated,
+                intensity,
+                expression: found.kind,
+                ironic: false,
+            });
+            matched_clauses.push(clauses[position]);
+            position = end;
+        }
+        EntrySentiment::new(0, 0, counted, matched)
+    }
+
+    /// Finds the longest lexicon entry starting at `position`: a phrase, an expression, the word
+    /// as written, a negative form of a lexicon verb, or a stem of the word, in that order.
+    /// Elongated words are retried with their repeated letters collapsed.
+    fn match_at<'a>(&'a self, words: &[&str], position: usize) -> Option<LexiconMatch<'a>> {
+        let word = words[position];
+        if self.rules.phrases {
+            let index = self.phrase_index.get_or_init(|| PhraseIndex::new(&self.lexicon));
+            if index.first_words.contains(word) {
+                let longest = index.max_words.min(words.len() - position);
+                for length in (2..=longest).rev() {
+                    if let Some(found) = self.match_phrase(&words[position..position + length]) {
+                        return Some(found);
+                    }
+                }
+            }
+        }
+        if self.is_stopword(word) || self.rules.negators.contains(word) || self.rules.intensifiers.contains_key(word) {
+            return None;
+        }
+        self.match_word(word).or_else(|| collapse_elongation(word).iter().find_map(|form| self.match_word(form)))
+    }
+
+    /// Whether a word is skipped as a stopword; sentiment-bearing stopwords such as `iyi` are not.
+    fn is_stopword(&self, word: &str) -> bool {
+        self.stopwords.contains(word) && !SENTIMENT_STOPWORDS.contains(&word)
+    }
+
+    /// Looks up a single word in the expression lexicon, then in the sentiment lexicon.
+    fn match_word<'a>(&'a self, word: &str) -> Option<LexiconMatch<'a>> {
+        let expression_key = match word.contains(is_emoji_modifier) {
+            true => Cow::Owned(strip_emoji_modifiers(word)),
+            false => Cow::Borrowed(word),
+        };
+        if let Some((key, expression)) = self.expressions.get_key_value(expression_key.as_ref()) {
+            if expression.kind != ExpressionKind::Irony || self.rules.irony {
+                let polarity = if expression.tone > 0.0 { 1 } else if expression.tone < 0.0 { -1 } else { 0 };
+                return Some(LexiconMatch {
+                    words: 1,
+                    key,
+                    sentiment: Sentiment { tone: expression.tone, polarity },
+                    negative_verb: false,
+                    kind: Some(expression.kind),
+                });
+            }
+            return None;
+        }
+        let single = |(key, sentiment): (&'a str, &Sentiment), negative_verb| LexiconMatch { words: 1, key, sentiment: *sentiment, negative_verb, kind: None };
+        if let Some((key, sentiment)) = self.lexicon.get_key_value(word) {
+            return Some(single((key, sentiment), false));
+        }
+        if self.rules.verb_negation {
+            if let Some(found) = negative_verb_roots(word).iter().find_map(|root| self.lexicon.get_key_value(root)) {
+                return Some(single((found.0, found.1), true));
+            }
+        }
+        lookup_sentiment(&self.lexicon, word, self.stemmer.as_ref()).map(|found| single(found, false))
+    }
+
+    /// Looks up a phrase as written, then with its last word stemmed (`a vitaminleri`).
+    fn match_phrase(&self, words: &[&str]) -> Option<LexiconMatch<'_>> {
+        let (last, head) = words.split_last()?;
+        let head = head.join(" ");
+        let last_forms = match &self.stemmer {
+            Some(stemmer) => stemmer.candidates(last),
+            None => vec![last.to_string()],
+        };
+        last_forms.iter().find_map(|form| {
+            self.lexicon.get_key_value(&format!("{} {}", head, form)).map(|(key, sentiment)| LexiconMatch {
+                words: words.len(),
+                key,
+                sentiment: *sentiment,
+                negative_verb: false,
+                kind: None,
+            })
+        })
+    }
+}
+
+impl PhraseIndex {
+    fn new(lexicon: &HashMap<String, Sentiment>) -> Self {
+        let mut index = PhraseIndex::default();
+        for key in lexicon.keys().filter(|key| key.contains(' ')) {
+            let words: Vec<&str> = key.split(' ').collect();
+            index.first_words.insert(words[0].to_string());
+            index.max_words = index.max_words.max(words.len());
+        }
+        index
+    }
+}
+
+/// Returns the lexicon forms a negative verb may be built on, longest first:
+/// `sevmiyorum` gives `sev` and `sevmek`.
+fn negative_verb_roots(word: &str) -> Vec<String> {
+    let mut roots = Vec::new();
+    for (index, _) in word.char_indices().rev() {
+        let (root, rest) = word.split_at(index);
+        if root.chars().count() < 2 || !NEGATIVE_VERB_SUFFIXES.iter().any(|suffix| rest.starts_with(suffix)) {
+            continue;
+        }
+        let back = root.chars().rev().find(|c| "aeıioöuü".contains(*c)).map(|c| "aıou".contains(c));
+        let Some(back) = back else { continue };
+        roots.push(root.to_string());
+        roots.push(format!("{}{}", root, if back { "mak" } else { "mek" }));
+    }
+    roots
+}
+
+/// Returns the forms of an elongated word (`güzeeeel`, `xddd`) with every run of three or more
+/// equal letters collapsed to one letter and to two letters; empty if the word has no such run.
+fn collapse_elongation(word: &str) -> Vec<String> {
+    let mut forms = [String::new(), String::new()];
+    let mut elongated = false;
+    let chars: Vec<char> = word.chars().collect();
+    let mut start = 0;
+    while start < chars.len() {
+        let run = chars[start..].iter().take_while(|&&c| c == chars[start]).count();
+        elongated |= run >= 3 && chars[start].is_alphabetic();
+        let kept = if run >= 3 && chars[start].is_alphabetic() { [1, 2] } else { [run, run] };
+        for (form, kept) in forms.iter_mut().zip(kept) {
+            form.extend(std::iter::repeat(chars[start]).take(kept));
+        }
+        start += run;
+    }
+    if elongated { forms.into() } else { Vec::new() }
+}
+
+/// A word or phrase of an entry that was found in the sentiment or expression lexicon.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchedWord {
+    /// The word as it appeared in the preprocessed entry, or the words of a phrase joined by spaces.
+    pub word: String,
+    /// The lexicon key it matched, which differs from `word` when it matched through a stem.
+    pub lexicon_word: String,
+    /// The lexicon tone after negation and intensifiers.
+    pub tone: f32,
+    /// The lexicon polarity, flipped when the word is negated.
+    pub polarity: i32,
+    /// Whether the word was negated (`güzel değil`, `sevmedim`).
+    pub negated: bool,
+    /// Product of the intensifier factors applied to the tone, `1` if there were none.
+    pub intensity: f32,
+    /// The kind of expression it matched, `None` for sentiment lexicon words.
+    pub expression: Option<ExpressionKind>,
+    /// Whether an irony marker (`(!)`) in the same clause applied its factor to the tone.
+    pub ironic: bool,
+}
+
+/// Sentiment scores of a single entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EntrySentiment {
+    /// Position of the entry in the analyzed list.
+    pub index: usize,
+    /// Eksi Sozluk id of the entry, `0` if unknown.
+    pub entry_id: u64,
+    /// Sum of the tones of the matched words.
+    pub tone: f32,
+    /// Number of matched words with positive polarity.
+    pub positive: usize,
+    /// Number of matched words with negative polarity.
+    pub negative: usize,
+    /// Number of tokens that are not stopwords.
+    pub tokens: usize,
+    /// Share of the tokens that matched the lexicon, between 0 and 1.
+    pub coverage: f32,
+    /// The matched words in the order they appear.
+    pub matched: Vec<MatchedWord>,
+}
+
+impl EntrySentiment {
+    /// Creates the scores of an entry from its matched words.
+    pub fn new(index: usize, entry_id: u64, tokens: usize, matched: Vec<MatchedWord>) -> Self {
+        let tone = matched.iter().fold(0.0, |total, m| total + m.tone);
+        let positive = matched.iter().filter(|m| m.polarity > 0).count();
+        let negative = matched.iter().filter(|m| m.polarity < 0).count();
+        let coverage = if tokens == 0 { 0.0 } else { (matched.len() as f32 / tokens as f32).min(1.0) };
+        EntrySentiment { index, entry_id, tone, positive, negative, tokens, coverage, matched }
+    }
+
+    /// Average tone of the matched words, `0` if nothing matched.
+    pub fn mean_tone(&self) -> f32 {
+        if self.matched.is_empty() { 0.0 } else { self.tone / self.matched.len() as f32 }
+    }
+}
+
+/// Sentiment scores of a list of entries together with corpus aggregates.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SentimentReport {
+    /// Scores of every analyzed entry, in input order.
+    pub entries: Vec<EntrySentiment>,
+    /// Sum of the tones of all matched words.
+    pub total_tone: f32,
+    /// Sum of the polarities of all matched words.
+    pub total_polarity: i32,
+    /// Sum of the tones of positive words.
+    pub positive_tone: f32,
+    /// Number of positive words.
+    pub positive_count: usize,
+    /// Sum of the tones of negative words.
+    pub negative_tone: f32,
+    /// Number of negative words.
+    pub negative_count: usize,
+    /// Number of tokens in all entries after preprocessing.
+    pub token_count: usize,
+    /// Number of tokens that matched the lexicon.
+    pub matched_count: usize,
+    /// Share of all tokens that matched the lexicon, between 0 and 1.
+    pub coverage: f32,
+    /// Average of the per-entry tones.
+    pub mean_entry_tone: f32,
+}
+
+impl SentimentReport {
+    /// Builds a report and its aggregates from per-entry scores.
+    pub fn from_entries(entries: Vec<EntrySentiment>) -> Self {
+        let mut report = SentimentReport::default();
+        for entry in &entries {
+            for word in &entry.matched {
+                report.total_tone += word.tone;
+                report.total_polarity += word.polarity;
+                if word.polarity == 1 {
+                    report.positive_tone += word.tone;
+                    report.positive_count += 1;
+                } else if word.polarity == -1 {
+                    report.negative_tone += word.tone;
+                    report.negative_count += 1;
+                }
+            }
+            report.token_count += entry.tokens;
+            report.matched_count += entry.matched.len();
+        }
+        if report.token_count > 0 {
+            report.coverage = (report.matched_count as f32 / report.token_count as f32).min(1.0);
+        }
+        if !entries.is_empty() {
+            report.mean_entry_tone = entries.iter().map(|e| e.tone).sum::<f32>() / entries.len() as f32;
+        }
+        report.entries = entries;
+        report
+    }
+}
+
+impl fmt::Display for SentimentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "+---------------------------------------+")?;
+        writeln!(f, "|            Sentiment Analysis         |")?;
+        writeln!(f, "+---------------------------------------+")?;
+        writeln!(f, "| Total Tone:                {:>10.2}  |", self.total_tone)?;
+        writeln!(f, "| Total Polarity:            {:>10}  |", self.total_polarity)?;
+        writeln!(f, "+---------------------------------------+")?;
+        writeln!(f, "| Positive Tone:             {:>10.2}  |", self.positive_tone)?;
+        writeln!(f, "| Positive Polarity Count:   {:>10}  |", self.positive_count)?;
+        writeln!(f, "+---------------------------------------+")?;
+        writeln!(f, "| Negative Tone:             {:>10.2}  |", self.negative_tone)?;
+        writeln!(f, "| Negative Polarity Count:   {:>10}  |", self.negative_count)?;
+        writeln!(f, "+---------------------------------------+")?;
+        writeln!(f, "| Lexicon Coverage:          {:>9.1}%  |", self.coverage * 100.0)?;
+        write!(f, "+---------------------------------------+")
+    }
+}
@@ -0,0 +1,665 @@
+//! # RustySozluk Similarity
+//!
+//! Bag-of-words vectors for entries: TF-IDF weighting, cosine similarity, nearest-neighbour
+//! queries, and k-means and agglomerative clustering.
+//!
+//! Term counts come from `word_frequencies_with`, so entries are tokenized and filtered exactly
+//! like in the other analyses. Vectors are sparse and normalized to unit length, which makes the
+//! cosine similarity of two entries the dot product of their vectors.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::analyzer::analyzer::word_frequencies_with;
+use crate::analyzer::rng::Rng;
+use crate::analyzer::stemmer::Stemmer;
+use crate::parser::Entry;
+
+/// A sparse vector of `(term id, weight)` pairs sorted by term id.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SparseVector {
+    /// The non-zero components, sorted by term id.
+    pub terms: Vec<(usize, f64)>,
+}
+
+impl SparseVector {
+    /// Returns true if the vector has no non-zero component, e.g. for an entry made of stopwords.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Returns the dot product with another sparse vector.
+    pub fn dot(&self, other: &SparseVector) -> f64 {
+        let (mut i, mut j, mut sum) = (0, 0, 0.0);
+        while i < self.terms.len() && j < other.terms.len() {
+            let (a, b) = (self.terms[i], other.terms[j]);
+            match a.0.cmp(&b.0) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    sum += a.1 * b.1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum
+    }
+
+    /// Returns the Euclidean length of the vector.
+    pub fn norm(&self) -> f64 {
+        self.terms.iter().map(|(_, weight)| weight * weight).sum::<f64>().sqrt()
+    }
+
+    fn dot_dense(&self, dense: &[f64]) -> f64 {
+        self.terms.iter().map(|&(term, weight)| weight * dense[term]).sum()
+    }
+}
+
+/// Returns the cosine similarity of two vectors, 0 if either of them is empty.
+///
+/// # Arguments
+///
+/// * `a` - The first vector.
+/// * `b` - The second vector.
+///
+/// # Returns
+///
+/// An `f64` between -1 and 1; TF-IDF vectors have no negative weights, so their similarity is
+/// between 0 and 1.
+pub fn cosine_similarity(a: &SparseVector, b: &SparseVector) -> f64 {
+    let norms = a.norm() * b.norm();
+    if norms == 0.0 {
+        0.0
+    } else {
+        a.dot(b) / norms
+    }
+}
+
+/// Turns entries into TF-IDF vectors.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::similarity::TfIdfVectorizer;
+///
+/// let texts = ["rust hızlı bir dil", "python yavaş", "rust hızlı ve güvenli"];
+/// let vectors = TfIdfVectorizer::new().fit_texts(texts, &HashSet::new());
+/// assert_eq!(vectors.nearest(0, 1)[0].index, 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TfIdfVectorizer {
+    stemmer: Option<Stemmer>,
+    min_document_frequency: usize,
+    max_document_ratio: f64,
+    sublinear_tf: bool,
+}
+
+impl Default for TfIdfVectorizer {
+    fn default() -> Self {
+        TfIdfVectorizer::new()
+    }
+}
+
+impl TfIdfVectorizer {
+    /// Creates a vectorizer that keeps every word and weights raw term counts.
+    pub fn new() -> Self {
+        TfIdfVectorizer {
+            stemmer: None,
+            min_document_frequency: 1,
+            max_document_ratio: 1.0,
+            sublinear_tf: false,
+        }
+    }
+
+    /// Sets the stemmer applied to every word; `None` (the default) keeps words as written.
+    pub fn stemmer(mut self, stemmer: Option<Stemmer>) -> Self {
+        self.stemmer = stemmer;
+        self
+    }
+
+    /// Sets the number of entries a word has to appear in to become a dimension.
+    pub fn min_document_frequency(mut self, min_document_frequency: usize) -> Self {
+        self.min_document_frequency = min_document_frequency;
+        self
+    }
+
+    /// Sets the largest share of entries, between 0 and 1, a word may appear in to become a
+    /// dimension.
+    pub fn max_document_ratio(mut self, max_document_ratio: f64) -> Self {
+        self.max_document_ratio = max_document_ratio;
+        self
+    }
+
+    /// Uses `1 + ln(count)` instead of the raw count as term frequency, so that a word repeated
+    /// many times in one entry does not dominate its vector.
+    pub fn sublinear_tf(mut self, sublinear_tf: bool) -> Self {
+        self.sublinear_tf = sublinear_tf;
+        self
+    }
+
+    /// Builds the vocabulary from entries and returns their vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to vectorize.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    ///
+    /// # Returns
+    ///
+    /// `EntryVectors` with one vector per entry, in input order.
+    pub fn fit(&self, entries: &[Entry], stopwords: &HashSet<String>) -> EntryVectors {
+        self.fit_texts(entries.iter().map(|entry| entry.content.as_str()), stopwords)
+    }
+
+    /// Builds the vocabulary from texts and returns their vectors like `fit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - The texts to vectorize.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    ///
+    /// # Returns
+    ///
+    /// `EntryVectors` with one vector per text, in input order.
+    pub fn fit_texts<I, S>(&self, texts: I, stopwords: &HashSet<String>) -> EntryVectors
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let counts: Vec<HashMap<String, usize>> = texts
+            .into_iter()
+            .map(|text| word_frequencies_with(vec![text.as_ref().to_string()], stopwords, self.stemmer.as_ref()))
+            .collect();
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        for words in &counts {
+            for word in words.keys() {
+                *document_frequency.entry(word).or_insert(0) += 1;
+            }
+        }
+
+        let total_documents = counts.len() as f64;
+        let max_documents = (self.max_document_ratio * total_documents).max(1.0);
+        let mut vocabulary: Vec<String> = document_frequency
+            .iter()
+            .filter(|&(_, &frequency)| frequency >= self.min_document_frequency && frequency as f64 <= max_documents)
+            .map(|(word, _)| word.to_string())
+            .collect();
+        vocabulary.sort();
+        let idf = vocabulary
+            .iter()
+            .map(|word| ((1.0 + total_documents) / (1.0 + document_frequency[word.as_str()] as f64)).ln() + 1.0)
+            .collect();
+        let index = vocabulary.iter().enumerate().map(|(id, word)| (word.clone(), id)).collect();
+
+        let mut vectors = EntryVectors {
+            vocabulary,
+            idf,
+            index,
+            stemmer: self.stemmer.clone(),
+            sublinear_tf: self.sublinear_tf,
+            vectors: Vec::new(),
+        };
+        vectors.vectors = counts.iter().map(|words| vectors.weigh(words)).collect();
+        vectors
+    }
+}
+
+/// An entry found by a nearest-neighbour query.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Neighbor {
+    /// Position of the entry in the vectorized slice.
+    pub index: usize,
+    /// Cosine similarity to the query, between 0 and 1.
+    pub similarity: f64,
+}
+
+/// TF-IDF vectors of a set of entries together with the vocabulary they were built from.
+#[derive(Debug, Clone)]
+pub struct EntryVectors {
+    /// The words of the vocabulary; the term ids of the vectors are positions in this list.
+    pub vocabulary: Vec<String>,
+    /// The inverse document frequency of every word of the vocabulary.
+    pub idf: Vec<f64>,
+    /// The unit-length vector of every entry, in input order.
+    pub vectors: Vec<SparseVector>,
+    index: HashMap<String, usize>,
+    stemmer: Option<Stemmer>,
+    sublinear_tf: bool,
+}
+
+impl EntryVectors {
+    /// Returns the number of vectors.
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    /// Returns true if there are no vectors.
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    /// Returns the cosine similarity of two entries, 0 if an index is out of range.
+    pub fn similarity(&self, a: usize, b: usize) -> f64 {
+        match (self.vectors.get(a), self.vectors.get(b)) {
+            (Some(a), Some(b)) => a.dot(b),
+            _ => 0.0,
+        }
+    }
+
+    /// Vectorizes a new text with the vocabulary and document frequencies of these vectors.
+    /// Words outside the vocabulary are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to vectorize.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    ///
+    /// # Returns
+    ///
+    /// A unit-length `SparseVector`, empty if no word of the text is in the vocabulary.
+    pub fn vectorize(&self, text: &str, stopwords: &HashSet<String>) -> SparseVector {
+        self.weigh(&word_frequencies_with(vec![text.to_string()], stopwords, self.stemmer.as_ref()))
+    }
+
+    /// Returns the entries most similar to an entry of the set, excluding the entry itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the entry to compare against.
+    /// * `k` - The number of neighbours to return.
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` neighbours with a positive similarity, most similar first.
+    pub fn nearest(&self, index: usize, k: usize) -> Vec<Neighbor> {
+        match self.vectors.get(index) {
+            Some(query) => self.ranked(query, k, Some(index)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the entries most similar to an arbitrary vector, e.g. one from `vectorize`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The vector to compare against.
+    /// * `k` - The number of neighbours to return.
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` neighbours with a positive similarity, most similar first.
+    pub fn nearest_to(&self, query: &SparseVector, k: usize) -> Vec<Neighbor> {
+        let norm = query.norm();
+        let mut neighbors = self.ranked(query, k, None);
+        for neighbor in &mut neighbors {
+            neighbor.similarity /= norm;
+        }
+        neighbors
+    }
+
+    /// Returns the highest weighted words of a vector.
+    pub fn top_terms(&self, vector: &SparseVector, n: usize) -> Vec<(String, f64)> {
+        let mut terms = vector.terms.clone();
+        terms.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        terms.into_iter().take(n).map(|(term, weight)| (self.vocabulary[term].clone(), weight)).collect()
+    }
+
+    fn ranked(&self, query: &SparseVector, k: usize, skip: Option<usize>) -> Vec<Neighbor> {
+        let mut neighbors: Vec<Neighbor> = self
+            .vectors
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| Some(index) != skip)
+            .map(|(index, vector)| Neighbor { index, similarity: query.dot(vector) })
+            .filter(|neighbor| neighbor.similarity > 0.0)
+            .collect();
+        neighbors.sort_by(|a, b| b.similarity.total_cmp(&a.similarity).then(a.index.cmp(&b.index)));
+        neighbors.truncate(k);
+        neighbors
+    }
+
+    fn weigh(&self, words: &HashMap<String, usize>) -> SparseVector {
+        let mut terms: Vec<(usize, f64)> = words
+            .iter()
+            .filter_map(|(word, &count)| {
+                let term = *self.index.get(word)?;
+                let tf = if self.sublinear_tf { 1.0 + (count as f64).ln() } else { count as f64 };
+                Some((term, tf * self.idf[term]))
+            })
+            .collect();
+        terms.sort_by_key(|&(term, _)| term);
+        let mut vector = SparseVector { terms };
+        let norm = vector.norm();
+        if norm > 0.0 {
+            for (_, weight) in &mut vector.terms {
+                *weight /= norm;
+            }
+        }
+        vector
+    }
+
+    /// Builds the clustering result from a label per vector, ordering clusters by size.
+    fn clustering(&self, labels: Vec<Option<usize>>) -> Clustering {
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (index, label) in labels.iter().enumerate() {
+            if let Some(label) = label {
+                groups.entry(*label).or_default().push(index);
+            }
+        }
+        let mut groups: Vec<Vec<usize>> = groups.into_values().collect();
+        groups.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+
+        let mut labels = vec![None; labels.len()];
+        let clusters = groups
+            .into_iter()
+            .enumerate()
+            .map(|(label, members)| {
+                let mut centroid = vec![0.0; self.vocabulary.len()];
+                for &member in &members {
+                    labels[member] = Some(label);
+                    for &(term, weight) in &self.vectors[member].terms {
+                        centroid[term] += weight / members.len() as f64;
+                    }
+                }
+                let centroid = SparseVector { terms: centroid.into_iter().enumerate().filter(|&(_, weight)| weight > 0.0).collect() };
+                let norm = centroid.norm();
+                let cohesion = members.iter().map(|&member| self.vectors[member].dot(&centroid) / norm).sum::<f64>() / members.len() as f64;
+                Cluster { top_terms: self.top_terms(&centroid, 10), members, cohesion }
+            })
+            .collect();
+        Clustering { labels, clusters }
+    }
+}
+
+/// A cluster of similar entries.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cluster {
+    /// Positions of the entries in the vectorized slice, in ascending order.
+    pub members: Vec<usize>,
+    /// The highest weighted words of the cluster centroid.
+    pub top_terms: Vec<(String, f64)>,
+    /// Average cosine similarity of the members to the centroid; higher is tighter.
+    pub cohesion: f64,
+}
+
+/// The result of a clustering.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Clustering {
+    /// The cluster of every entry, in input order. Entries with an empty vector are not clustered.
+    pub labels: Vec<Option<usize>>,
+    /// The clusters, largest first; labels are positions in this list.
+    pub clusters: Vec<Cluster>,
+}
+
+/// Spherical k-means clustering of entry vectors, seeded with k-means++.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::similarity::{KMeans, TfIdfVectorizer};
+///
+/// let texts = ["maç gol hakem", "hakem penaltı gol", "dolar faiz enflasyon", "faiz enflasyon kur"];
+/// let vectors = TfIdfVectorizer::new().fit_texts(texts, &HashSet::new());
+/// let clustering = KMeans::new(2).fit(&vectors);
+/// assert_eq!(clustering.labels[0], clustering.labels[1]);
+/// assert_ne!(clustering.labels[0], clustering.labels[2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KMeans {
+    k: usize,
+    iterations: usize,
+    seed: u64,
+}
+
+impl KMeans {
+    /// Creates a k-means clustering into `k` clusters (at least 1) with up to 100 iterations.
+    pub fn new(k: usize) -> Self {
+        KMeans { k: k.max(1), iterations: 100, seed: 42 }
+    }
+
+    /// Sets the largest number of assignment and update rounds.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the seed of the initial centroids. Results are reproducible for the same seed.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Clusters the vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `vectors` - The vectors to cluster.
+    ///
+    /// # Returns
+    ///
+    /// A `Clustering` with at most `k` clusters.
+    pub fn fit(&self, vectors: &EntryVectors) -> Clustering {
+        let points: Vec<usize> = (0..vectors.len()).filter(|&index| !vectors.vectors[index].is_empty()).collect();
+        let mut labels = vec![None; vectors.len()];
+        if points.is_empty() {
+            return vectors.clustering(labels);
+        }
+        let dimensions = vectors.vocabulary.len();
+        let dense = |point: usize| {
+            let mut centroid = vec![0.0; dimensions];
+            for &(term, weight) in &vectors.vectors[point].terms {
+                centroid[term] = weight;
+            }
+            centroid
+        };
+
+        let mut rng = Rng::new(self.seed);
+        let mut centroids = vec![dense(points[rng.below(points.len())])];
+        let mut distances: Vec<f64> = points.iter().map(|&point| 1.0 - vectors.vectors[point].dot_dense(&centroids[0])).collect();
+        while centroids.len() < self.k.min(points.len()) {
+            let total: f64 = distances.iter().map(|distance| distance * distance).sum();
+            if total <= 0.0 {
+                break;
+            }
+            let mut target = rng.next_f64() * total;
+            let mut chosen = points.len() - 1;
+            for (position, distance) in distances.iter().enumerate() {
+                if target < distance * distance {
+                    chosen = position;
+                    break;
+                }
+                target -= distance * distance;
+            }
+            centroids.push(dense(points[chosen]));
+            let centroid = centroids.last().unwrap();
+            for (position, &point) in points.iter().enumerate() {
+                distances[position] = distances[position].min(1.0 - vectors.vectors[point].dot_dense(centroid));
+            }
+        }
+
+        let mut assignment = vec![usize::MAX; points.len()];
+        for _ in 0..self.iterations.max(1) {
+            let mut changed = false;
+            let mut similarities = vec![0.0; points.len()];
+            for (position, &point) in points.iter().enumerate() {
+                let (best, similarity) = centroids
+                    .iter()
+                    .enumerate()
+                    .map(|(cluster, centroid)| (cluster, vectors.vectors[point].dot_dense(centroid)))
+                    .fold((0, f64::NEG_INFINITY), |best, candidate| if candidate.1 > best.1 { candidate } else { best });
+                similarities[position] = similarity;
+                if assignment[position] != best {
+                    assignment[position] = best;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+
+            let mut sums = vec![vec![0.0; dimensions]; centroids.len()];
+            let mut sizes = vec![0usize; centroids.len()];
+            for (position, &point) in points.iter().enumerate() {
+                sizes[assignment[position]] += 1;
+                for &(term, weight) in &vectors.vectors[point].terms {
+                    sums[assignment[position]][term] += weight;
+                }
+            }
+            for (cluster, mut sum) in sums.into_iter().enumerate() {
+                if sizes[cluster] == 0 {
+                    // Restart an empty cluster at the point that fits its own cluster worst.
+                    let worst = (0..points.len()).min_by(|&a, &b| similarities[a].total_cmp(&similarities[b])).unwrap();
+                    similarities[worst] = f64::INFINITY;
+                    sum = dense(points[worst]);
+                }
+                let norm = sum.iter().map(|weight| weight * weight).sum::<f64>().sqrt();
+                if norm > 0.0 {
+                    sum.iter_mut().for_each(|weight| *weight /= norm);
+                }
+                centroids[cluster] = sum;
+            }
+        }
+
+        for (position, &point) in points.iter().enumerate() {
+            labels[point] = Some(assignment[position]);
+        }
+        vectors.clustering(labels)
+    }
+}
+
+/// How the distance between two clusters is derived from the distances of their members.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Linkage {
+    /// Distance of the closest pair of members; finds elongated clusters.
+    Single,
+    /// Distance of the farthest pair of members; finds compact clusters.
+    Complete,
+    /// Mean distance of all pairs of members.
+    #[default]
+    Average,
+}
+
+/// Bottom-up hierarchical clustering of entry vectors with cosine distance.
+///
+/// Every entry starts as its own cluster and the two closest clusters are merged until `k`
+/// clusters remain. Memory grows with the square of the number of entries, which is fine for a
+/// few thousand entries; use `KMeans` for larger sets.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::similarity::{Agglomerative, Linkage, TfIdfVectorizer};
+///
+/// let texts = ["maç gol hakem", "hakem penaltı gol", "dolar faiz enflasyon", "faiz enflasyon kur"];
+/// let vectors = TfIdfVectorizer::new().fit_texts(texts, &HashSet::new());
+/// let clustering = Agglomerative::new(2).linkage(Linkage::Complete).fit(&vectors);
+/// assert_eq!(clustering.clusters[0].members, vec![0, 1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Agglomerative {
+    k: usize,
+    linkage: Linkage,
+}
+
+impl Agglomerative {
+    /// Creates an agglomerative clustering into `k` clusters (at least 1) with average linkage.
+    pub fn new(k: usize) -> Self {
+        Agglomerative { k: k.max(1), linkage: Linkage::default() }
+    }
+
+    /// Sets the linkage criterion.
+    pub fn linkage(mut self, linkage: Linkage) -> Self {
+        self.linkage = linkage;
+        self
+    }
+
+    /// Clusters the vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `vectors` - The vectors to cluster.
+    ///
+    /// # Returns
+    ///
+    /// A `Clustering` with at most `k` clusters.
+    pub fn fit(&self, vectors: &EntryVectors) -> Clustering {
+        let points: Vec<usize> = (0..vectors.len()).filter(|&index| !vectors.vectors[index].is_empty()).collect();
+        let n = points.len();
+        // Condensed upper triangle of the distance matrix; f32 halves the memory.
+        let offset = |a: usize, b: usize| {
+            let (a, b) = if a < b { (a, b) } else { (b, a) };
+            a * n - a * (a + 1) / 2 + (b - a - 1)
+        };
+        let mut distances = vec![0f32; n * n.saturating_sub(1) / 2];
+        for a in 0..n {
+            for b in a + 1..n {
+                distances[offset(a, b)] = (1.0 - vectors.vectors[points[a]].dot(&vectors.vectors[points[b]])) as f32;
+            }
+        }
+
+        // Nearest-neighbour chain: follow nearest neighbours until two clusters are each other's
+        // nearest, merge them, and continue from the rest of the chain.
+        let mut active = vec![true; n];
+        let mut sizes = vec![1usize; n];
+        let mut merges: Vec<(f32, usize, usize)> = Vec::with_capacity(n.saturating_sub(1));
+        let mut chain: Vec<usize> = Vec::new();
+        while merges.len() + 1 < n {
+            if chain.is_empty() {
+                chain.push(active.iter().position(|&alive| alive).unwrap());
+            }
+            let current = *chain.last().unwrap();
+            let previous = chain.len().checked_sub(2).map(|position| chain[position]);
+            let mut nearest = previous.unwrap_or(usize::MAX);
+            let mut best = previous.map_or(f32::INFINITY, |previous| distances[offset(current, previous)]);
+            for other in (0..n).filter(|&other| active[other] && other != current) {
+                let distance = distances[offset(current, other)];
+                if distance < best || (distance == best && other < nearest && Some(nearest) != previous) {
+                    best = distance;
+                    nearest = other;
+                }
+            }
+            if Some(nearest) != previous {
+                chain.push(nearest);
+                continue;
+            }
+
+            chain.truncate(chain.len() - 2);
+            let (kept, removed) = (current.min(nearest), current.max(nearest));
+            merges.push((best, kept, removed));
+            active[removed] = false;
+            for other in (0..n).filter(|&other| active[other] && other != kept) {
+                let (to_kept, to_removed) = (distances[offset(kept, other)], distances[offset(removed, other)]);
+                distances[offset(kept, other)] = match self.linkage {
+                    Linkage::Single => to_kept.min(to_removed),
+                    Linkage::Complete => to_kept.max(to_removed),
+                    Linkage::Average => (to_kept * sizes[kept] as f32 + to_removed * sizes[removed] as f32) / (sizes[kept] + sizes[removed]) as f32,
+                };
+            }
+            sizes[kept] += sizes[removed];
+        }
+
+        // The chain finds merges out of order; replaying the closest ones gives the same tree.
+        merges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut parent: Vec<usize> = (0..n).collect();
+        fn root(parent: &mut [usize], mut node: usize) -> usize {
+            while parent[node] != node {
+                parent[node] = parent[parent[node]];
+                node = parent[node];
+            }
+            node
+        }
+        for &(_, a, b) in merges.iter().take(n.saturating_sub(self.k)) {
+            let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+            parent[ra.max(rb)] = ra.min(rb);
+        }
+
+        let mut labels = vec![None; vectors.len()];
+        for (position, &point) in points.iter().enumerate() {
+            labels[point] = Some(root(&mut parent, position));
+        }
+        vectors.clustering(labels)
+    }
+}
@@ -0,0 +1,275 @@
+//! # RustySozluk Stemmer
+//!
+//! A light suffix-stripping stemmer for Turkish. Turkish is agglutinative, so `kitaplarımızdan`,
+//! `kitabı` and `kitaplar` should all be counted as `kitap`.
+//!
+//! The stemmer removes inflectional suffixes (case, possessive, plural, copula and a few tense
+//! suffixes) one at a time from the end of the word and undoes consonant softening
+//! (`kitab` → `kitap`, `ağac` → `ağaç`, `köpeğ` → `köpek`). Stripping is heuristic; when a
+//! dictionary of known words is given, strips that leave a known word are preferred and the
+//! longest known form is returned, which keeps words such as `masa`, `durum` or `hasta` intact.
+//! `Stemmer::embedded` uses the bundled lexicon and stopwords as its dictionary.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+#[cfg(feature = "embedded-resources")]
+use crate::analyzer::resources::default_dictionary;
+
+/// Inflectional suffixes, grouped so that longer alternatives are tried first.
+const SUFFIXES: &[&str] = &[
+    // possessive + plural combinations
+    "larımız", "lerimiz", "larınız", "leriniz",
+    "ımız", "imiz", "umuz", "ümüz", "ınız", "iniz", "unuz", "ünüz",
+    "mız", "miz", "muz", "müz", "nız", "niz", "nuz", "nüz",
+    "ları", "leri",
+    // case after a 3rd person possessive
+    "ından", "inden", "undan", "ünden", "ında", "inde", "unda", "ünde",
+    // ablative, locative, genitive, instrumental
+    "ndan", "nden", "dan", "den", "tan", "ten",
+    "nda", "nde", "da", "de", "ta", "te",
+    "nın", "nin", "nun", "nün",
+    "yla", "yle", "la", "le",
+    // copula, past and evidential
+    "dır", "dir", "dur", "dür", "tır", "tir", "tur", "tür",
+    "mış", "miş", "muş", "müş",
+    // past tense with 1st person endings
+    "dım", "dim", "dum", "düm", "tım", "tim", "tum", "tüm",
+    "dık", "dik", "duk", "dük", "tık", "tik", "tuk", "tük",
+    "dı", "di", "du", "dü", "tı", "ti", "tu", "tü",
+    // plural
+    "lar", "ler",
+    // dative, accusative and possessive with buffer consonants
+    "ya", "ye", "na", "ne",
+    "yı", "yi", "yu", "yü", "nı", "ni", "nu", "nü",
+    "sı", "si", "su", "sü",
+    "ın", "in", "un", "ün",
+    "ım", "im", "um", "üm",
+    // single vowels: accusative, dative and 3rd person possessive
+    "ı", "i", "u", "ü", "a", "e",
+    "m", "n",
+];
+
+/// Frequent words that look inflected but are not.
+const PROTECTED: &[&str] = &["türkiye", "diye", "gibi", "bile", "ile", "şimdi", "hani", "daha", "anne", "baba", "dünya", "hala", "kadın", "yarın", "yardım"];
+
+/// Shortest stem (in characters) that stripping may leave behind.
+const MIN_STEM_CHARS: usize = 2;
+/// Shortest stem (in characters) a single-letter suffix may leave behind; shorter words such as
+/// `zaman` or `sevgi` only look inflected.
+const MIN_SINGLE_LETTER_STEM_CHARS: usize = MIN_STEM_CHARS + 3;
+/// Maximum number of suffixes removed from a single word.
+const MAX_STRIPS: usize = 5;
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'ı' | 'i' | 'o' | 'ö' | 'u' | 'ü' | 'â' | 'î' | 'û')
+}
+
+/// Turkish suffix-stripping stemmer.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::stemmer::Stemmer;
+///
+/// let stemmer = Stemmer::new();
+/// assert_eq!(stemmer.stem("kitaplarımızdan"), "kitap");
+/// assert_eq!(stemmer.stem("kitabı"), "kitap");
+/// assert_eq!(stemmer.stem("kitaplar"), "kitap");
+/// assert_eq!(stemmer.stem("geldim"), "gel");
+///
+/// // words that only look inflected are left alone
+/// for word in ["deniz", "zaman", "insan", "dolar", "akşam", "orman", "yılan", "kötü", "sevgi"] {
+///     assert_eq!(stemmer.stem(word), word);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stemmer {
+    dictionary: Option<HashSet<String>>,
+    /// Whether the bundled words are the dictionary; they are shared, not copied or serialized.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    bundled: bool,
+}
+
+impl Stemmer {
+    /// Creates a stemmer that relies on suffix stripping only. Without a dictionary, words that
+    /// only look inflected (`durum`, `hasta`) are cut as well; prefer `embedded` or `with_dictionary`.
+    pub fn new() -> Self {
+        Stemmer { dictionary: None, bundled: false }
+    }
+
+    /// Creates a stemmer whose dictionary is the words of the bundled sentiment lexicon and
+    /// stopwords. The words are shared, not copied, so this is cheap to call repeatedly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustysozluk::analyzer::stemmer::Stemmer;
+    ///
+    /// let stemmer = Stemmer::embedded();
+    /// assert_eq!(stemmer.stem("evinden"), "ev");
+    /// assert_eq!(stemmer.stem("anladım"), "anla");
+    /// assert_eq!(stemmer.stem("geldim"), "gel");
+    ///
+    /// // known words and words without a known stem are left alone
+    /// for word in ["deprem", "bugün", "kendi", "sadece", "yardım", "durum", "sorun", "hasta", "bayram", "ilgili"] {
+    ///     assert_eq!(stemmer.stem(word), word);
+    /// }
+    /// ```
+    #[cfg(feature = "embedded-resources")]
+    pub fn embedded() -> Self {
+        Stemmer { dictionary: None, bundled: true }
+    }
+
+    /// Creates a stemmer that prefers stems found in the given dictionary,
+    /// for example the keys of a sentiment lexicon.
+    pub fn with_dictionary<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Stemmer { dictionary: Some(words.into_iter().map(Into::into).collect()), bundled: false }
+    }
+
+    /// Returns the progressively stripped forms of a word, starting with the word itself.
+    ///
+    /// Each form is followed by its hardened variant when the stem ends in a softened consonant.
+    /// With a dictionary, only the word itself and known forms are returned. The list is useful
+    /// for lexicon lookups: the first form found in the lexicon is the best match.
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - A lowercased word.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<String>` of candidate stems, longest first.
+    pub fn candidates(&self, word: &str) -> Vec<String> {
+        let mut candidates = vec![word.to_string()];
+        if PROTECTED.contains(&word) {
+            return candidates;
+        }
+        let mut current = word.to_string();
+        for strip in 0..MAX_STRIPS {
+            let Some((stripped, suffix)) = self.strip_suffix(&current, strip == 0) else { break };
+            // after a buffer consonant (`araba-sı`, `masa-ya`) the remaining vowel belongs to the root
+            let root_reached = suffix.starts_with(['y', 's', 'm']) && stripped.ends_with(is_vowel);
+            if let Some(hardened) = harden(&stripped, suffix) {
+                candidates.push(stripped.clone());
+                candidates.push(hardened.clone());
+                current = if self.is_known(&stripped) { stripped } else { hardened };
+            } else {
+                candidates.push(stripped.clone());
+                current = stripped;
+            }
+            if root_reached {
+                break;
+            }
+        }
+        candidates.dedup();
+        if self.dictionary().is_some() {
+            let mut forms = candidates.into_iter();
+            let word = forms.next();
+            candidates = word.into_iter().chain(forms.filter(|form| self.is_known(form))).collect();
+        }
+        candidates
+    }
+
+    /// Returns the stem of a word.
+    ///
+    /// With a dictionary, the longest candidate found in the dictionary is returned and the word
+    /// itself is returned if no candidate is known. Without one, every recognized suffix is stripped.
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - A lowercased word.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the stem.
+    pub fn stem(&self, word: &str) -> String {
+        let mut candidates = self.candidates(word);
+        match self.dictionary() {
+            Some(dictionary) => candidates
+                .into_iter()
+                .find(|candidate| dictionary.contains(candidate))
+                .unwrap_or_else(|| word.to_string()),
+            None => candidates.pop().unwrap_or_else(|| word.to_string()),
+        }
+    }
+
+    fn dictionary(&self) -> Option<&HashSet<String>> {
+        #[cfg(feature = "embedded-resources")]
+        if self.bundled {
+            return Some(default_dictionary());
+        }
+        self.dictionary.as_ref()
+    }
+
+    fn is_known(&self, word: &str) -> bool {
+        self.dictionary().is_some_and(|dictionary| dictionary.contains(word))
+    }
+
+    /// Removes a suffix from the word and returns the stem and the suffix.
+    ///
+    /// A suffix that leaves a known word is preferred (`kendi-nden` over `kend-inden`); otherwise
+    /// the longest suffix is removed.
+    fn strip_suffix(&self, word: &str, outermost: bool) -> Option<(String, &'static str)> {
+        let strips: Vec<(&str, &'static str)> = suffix_strips(word, outermost).collect();
+        strips
+            .iter()
+            .find(|(stem, _)| self.is_known(stem))
+            .or(strips.first())
+            .map(|(stem, suffix)| (stem.to_string(), *suffix))
+    }
+}
+
+/// Returns the suffixes that leave a valid stem behind, longest first, with their stems.
+///
+/// Single-letter suffixes are ambiguous and only considered for the outermost suffix of a word
+/// with a long enough stem.
+fn suffix_strips(word: &str, outermost: bool) -> impl Iterator<Item = (&str, &'static str)> {
+    let length = word.chars().count();
+    SUFFIXES.iter().filter_map(move |suffix| {
+        let stem = word.strip_suffix(suffix)?;
+        let stem_length = length - suffix.chars().count();
+        let single_letter = suffix.chars().count() == 1;
+        if single_letter && !outermost {
+            return None;
+        }
+        let min_length = if single_letter { MIN_SINGLE_LETTER_STEM_CHARS } else { MIN_STEM_CHARS };
+        if stem_length < min_length || !has_closed_syllable(stem) {
+            return None;
+        }
+        // a bare `m`/`n` is a possessive only after a vowel (`arabam`, `araban`)
+        if single_letter && !suffix.starts_with(is_vowel) && !stem.ends_with(is_vowel) {
+            return None;
+        }
+        Some((stem, *suffix))
+    })
+}
+
+/// Whether a stem has a vowel followed by a consonant (`ev`, `kitab`). Stems without one, such
+/// as `de` from `deniz` or `kö` from `kötü`, are almost always leftovers of over-stripping.
+fn has_closed_syllable(stem: &str) -> bool {
+    let chars: Vec<char> = stem.chars().collect();
+    chars.windows(2).any(|pair| is_vowel(pair[0]) && !is_vowel(pair[1]))
+}
+
+/// Undoes consonant softening at the end of a stem (`kitab` → `kitap`). Consonants only soften
+/// before a vowel, so stems left by a suffix starting with a consonant (`anla-dım`) are kept.
+fn harden(stem: &str, suffix: &str) -> Option<String> {
+    if !suffix.starts_with(is_vowel) {
+        return None;
+    }
+    let last = stem.chars().last()?;
+    let hard = match last {
+        'b' => 'p',
+        'c' => 'ç',
+        'ğ' => 'k',
+        'd' => 't',
+        _ => return None,
+    };
+    let mut hardened = stem[..stem.len() - last.len_utf8()].to_string();
+    hardened.push(hard);
+    Some(hardened)
+}
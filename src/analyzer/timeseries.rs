@@ -0,0 +1,284 @@
+//! # RustySozluk Time Series
+//!
+//! Buckets entries by hour, day, week or month of their creation date to follow how a discussion
+//! evolves. Every bucket holds the number of entries and authors, the favorites, the average
+//! sentiment and the most frequent words of its entries.
+//!
+//! A `TimeSeries` can be written as CSV or JSON for plotting.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use csv::Writer;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, Write};
+use crate::analyzer::analyzer::{top_words_in, TopWordsOptions};
+use crate::analyzer::sentiment::{SentimentAnalyzer, SentimentReport};
+use crate::parser::Entry;
+
+/// The length of a time bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum Interval {
+    /// A clock hour.
+    Hour,
+    /// A calendar day.
+    #[default]
+    Day,
+    /// A week starting on Monday.
+    Week,
+    /// A calendar month.
+    Month,
+}
+
+impl Interval {
+    /// Returns the start of the bucket that contains `time`.
+    pub fn bucket_start(self, time: NaiveDateTime) -> NaiveDateTime {
+        let date = time.date();
+        match self {
+            Interval::Hour => date.and_time(NaiveTime::from_hms_opt(time.time().hour(), 0, 0).unwrap_or_default()),
+            Interval::Day => date.and_time(NaiveTime::MIN),
+            Interval::Week => (date - Duration::days(date.weekday().num_days_from_monday() as i64)).and_time(NaiveTime::MIN),
+            Interval::Month => date.with_day(1).unwrap_or(date).and_time(NaiveTime::MIN),
+        }
+    }
+
+    /// Returns the start of the bucket after the one starting at `start`.
+    pub fn next_start(self, start: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Interval::Hour => start + Duration::hours(1),
+            Interval::Day => start + Duration::days(1),
+            Interval::Week => start + Duration::weeks(1),
+            Interval::Month => {
+                let (year, month) = if start.month() == 12 { (start.year() + 1, 1) } else { (start.year(), start.month() + 1) };
+                NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(start.date()).and_time(NaiveTime::MIN)
+            }
+        }
+    }
+}
+
+/// Options for `TimeSeries::build`.
+#[derive(Debug, Clone)]
+pub struct TimeSeriesOptions {
+    /// The length of a bucket.
+    pub interval: Interval,
+    /// Number of most frequent words kept per bucket.
+    pub keywords: usize,
+    /// Filters and stemming for the keywords.
+    pub keyword_options: TopWordsOptions,
+    /// Whether empty buckets between the first and the last entry are included, which keeps the
+    /// series evenly spaced for plotting. Every interval of the whole span gets a bucket, e.g.
+    /// about 26 000 for a title active over three years with `Interval::Hour`.
+    pub fill_gaps: bool,
+    /// Maximum number of empty buckets added by `fill_gaps`; later gaps are left out once it is reached.
+    pub max_filled_buckets: usize,
+    /// The analyzer used for the sentiment averages, `None` to skip sentiment.
+    /// Defaults to `SentimentAnalyzer::embedded()` with the `embedded-resources` feature.
+    pub sentiment: Option<SentimentAnalyzer>,
+}
+
+impl Default for TimeSeriesOptions {
+    fn default() -> Self {
+        TimeSeriesOptions {
+            interval: Interval::Day,
+            keywords: 5,
+            keyword_options: TopWordsOptions::default(),
+            fill_gaps: true,
+            max_filled_buckets: 10_000,
+            #[cfg(feature = "embedded-resources")]
+            sentiment: Some(SentimentAnalyzer::embedded()),
+            #[cfg(not(feature = "embedded-resources"))]
+            sentiment: None,
+        }
+    }
+}
+
+impl TimeSeriesOptions {
+    /// Creates the default options for the given interval.
+    pub fn new(interval: Interval) -> Self {
+        TimeSeriesOptions { interval, ..Default::default() }
+    }
+}
+
+/// Statistics of the entries created in one time bucket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeBucket {
+    /// Start of the bucket.
+    pub start: NaiveDateTime,
+    /// End of the bucket (exclusive).
+    pub end: NaiveDateTime,
+    /// Number of entries.
+    pub entries: usize,
+    /// Number of distinct authors.
+    pub authors: usize,
+    /// Sum of the favorite counts of the entries.
+    pub favorites: u64,
+    /// Average entry tone, `None` if sentiment was skipped or the bucket is empty.
+    pub mean_tone: Option<f32>,
+    /// Number of entries with a positive tone.
+    pub positive_entries: usize,
+    /// Number of entries with a negative tone.
+    pub negative_entries: usize,
+    /// The most frequent words, as returned by `top_words`.
+    pub keywords: Vec<(String, usize)>,
+}
+
+/// Entry statistics over time.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::timeseries::{Interval, TimeSeries, TimeSeriesOptions};
+/// # let entries: Vec<rustysozluk::Entry> = Vec::new();
+///
+/// let stopwords: HashSet<String> = HashSet::new();
+/// let series = TimeSeries::build(&entries, &stopwords, &TimeSeriesOptions::new(Interval::Hour));
+/// series.export_to_csv("timeline.csv").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeSeries {
+    /// The length of the buckets.
+    pub interval: Interval,
+    /// The buckets in chronological order.
+    pub buckets: Vec<TimeBucket>,
+    /// Number of entries left out because their date could not be parsed.
+    pub undated: usize,
+}
+
+impl TimeSeries {
+    /// Buckets entries by their creation date.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to analyze.
+    /// * `stopwords` - A reference to a HashSet containing stopwords, used for the keywords.
+    /// * `options` - The `TimeSeriesOptions` to apply.
+    ///
+    /// # Returns
+    ///
+    /// A `TimeSeries` with the buckets in chronological order.
+    pub fn build(entries: &[Entry], stopwords: &HashSet<String>, options: &TimeSeriesOptions) -> Self {
+        let mut dated: Vec<(NaiveDateTime, &Entry)> = Vec::new();
+        let mut undated = 0;
+        for entry in entries {
+            match entry.created_at() {
+                Some(created) => dated.push((options.interval.bucket_start(created), entry)),
+                None => undated += 1,
+            }
+        }
+        dated.sort_by_key(|(start, _)| *start);
+
+        let mut buckets = Vec::new();
+        let mut filled = 0;
+        let mut rest = dated.as_slice();
+        while let Some(&(start, _)) = rest.first() {
+            if options.fill_gaps {
+                if let Some(previous) = buckets.last().map(|bucket: &TimeBucket| bucket.end) {
+                    let mut gap = previous;
+                    while gap < start && filled < options.max_filled_buckets {
+                        buckets.push(Self::bucket(gap, options.interval.next_start(gap), &[], stopwords, options));
+                        gap = options.interval.next_start(gap);
+                        filled += 1;
+                    }
+                }
+            }
+            let length = rest.iter().take_while(|(other, _)| *other == start).count();
+            let bucket_entries: Vec<&Entry> = rest[..length].iter().map(|(_, entry)| *entry).collect();
+            buckets.push(Self::bucket(start, options.interval.next_start(start), &bucket_entries, stopwords, options));
+            rest = &rest[length..];
+        }
+        TimeSeries { interval: options.interval, buckets, undated }
+    }
+
+    fn bucket(start: NaiveDateTime, end: NaiveDateTime, entries: &[&Entry], stopwords: &HashSet<String>, options: &TimeSeriesOptions) -> TimeBucket {
+        let authors: HashSet<&str> = entries.iter().map(|entry| entry.username.as_str()).collect();
+        let mut bucket = TimeBucket {
+            start,
+            end,
+            entries: entries.len(),
+            authors: authors.len(),
+            favorites: entries.iter().map(|entry| entry.favorites as u64).sum(),
+            mean_tone: None,
+            positive_entries: 0,
+            negative_entries: 0,
+            keywords: top_words_in(entries.iter().map(|entry| entry.content.clone()).collect(), stopwords, options.keywords, &options.keyword_options),
+        };
+        if let Some(analyzer) = options.sentiment.as_ref().filter(|_| !entries.is_empty()) {
+            let report = SentimentReport::from_entries(entries.iter().map(|entry| analyzer.score_text(&entry.content)).collect());
+            bucket.mean_tone = Some(report.mean_entry_tone);
+            bucket.positive_entries = report.entries.iter().filter(|e| e.tone > 0.0).count();
+            bucket.negative_entries = report.entries.iter().filter(|e| e.tone < 0.0).count();
+        }
+        bucket
+    }
+
+    /// Writes the buckets as CSV, one row per bucket. Keywords are written as `word:count`
+    /// pairs separated by spaces.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination implementing `Write`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut wtr = Writer::from_writer(writer);
+        wtr.write_record(["start", "end", "entries", "authors", "favorites", "mean_tone", "positive_entries", "negative_entries", "keywords"])?;
+        for bucket in &self.buckets {
+            let keywords: Vec<String> = bucket.keywords.iter().map(|(word, count)| format!("{}:{}", word, count)).collect();
+            wtr.write_record(&[
+                bucket.start.format("%Y-%m-%d %H:%M").to_string(),
+                bucket.end.format("%Y-%m-%d %H:%M").to_string(),
+                bucket.entries.to_string(),
+                bucket.authors.to_string(),
+                bucket.favorites.to_string(),
+                bucket.mean_tone.map(|tone| tone.to_string()).unwrap_or_default(),
+                bucket.positive_entries.to_string(),
+                bucket.negative_entries.to_string(),
+                keywords.join(" "),
+            ])?;
+        }
+        wtr.flush()
+    }
+
+    /// Writes the buckets to a CSV file like `write_csv`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The path of the CSV file to create.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+    pub fn export_to_csv(&self, file_name: &str) -> io::Result<()> {
+        self.write_csv(File::create(file_name)?)
+    }
+
+    /// Writes the series as pretty-printed JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination implementing `Write`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+    pub fn write_json<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Writes the series to a JSON file like `write_json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The path of the JSON file to create.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either `Ok(())` if successful, or an `io::Error`.
+    pub fn export_to_json(&self, file_name: &str) -> io::Result<()> {
+        self.write_json(File::create(file_name)?)
+    }
+}
@@ -0,0 +1,387 @@
+//! # RustySozluk Tokenizer
+//!
+//! A tokenizer for Turkish text that knows about Turkish casing rules (`I` → `ı`, `İ` → `i`),
+//! splits apostrophe suffixes (`Türkiye'nin` → `türkiye` + `nin`) and recognizes URLs,
+//! hashtags, mentions, numbers, emoji and emoticons instead of throwing them away.
+
+/// Conjunctions that start a new clause (`güzel ama pahalı`).
+const CLAUSE_BREAKS: &[&str] = &["ama", "fakat", "ancak", "lakin", "oysa"];
+
+/// Emoticons that do not follow the eyes-nose-mouth pattern, and the irony markers of Sözlük writing.
+const FIXED_EMOTICONS: &[&str] = &["(!)", "(?)", "</3", "<3", "^_^", "^^", "-_-", "(:"];
+
+/// Mouths of eyes-nose-mouth emoticons (`:)`, `;-P`, `:'(`).
+const EMOTICON_MOUTHS: &str = ")(DPpSsOo/|*";
+
+/// The kind of a token produced by the `Tokenizer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TokenKind {
+    /// A word, possibly containing digits (`mp3`).
+    Word,
+    /// A number such as `42`, `3,5` or `1.000`.
+    Number,
+    /// A hashtag such as `#deprem`.
+    Hashtag,
+    /// A mention of a user such as `@ssg`.
+    Mention,
+    /// A web address starting with `http://`, `https://` or `www.`.
+    Url,
+    /// A single emoji, including skin tone and ZWJ sequences.
+    Emoji,
+    /// An ASCII emoticon such as `:)`, `:-(` or `<3`, or an irony marker such as `(!)`.
+    /// The text drops the nose and repeated mouths, so `:-)))` becomes `:)`.
+    Emoticon,
+}
+
+/// A single token of a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The normalized form: Turkish-lowercased (if enabled) and without the apostrophe suffix.
+    pub text: String,
+    /// The token exactly as it appears in the text, including any apostrophe suffix.
+    pub raw: String,
+    /// The kind of the token.
+    pub kind: TokenKind,
+    /// The suffix after an apostrophe (`nin` in `Türkiye'nin`), if it was split off.
+    pub suffix: Option<String>,
+    /// Byte offset of the token in the original text.
+    pub offset: usize,
+}
+
+/// Lowercases text following Turkish rules.
+///
+/// `I` becomes `ı` and `İ` becomes `i`, unlike `str::to_lowercase` which maps `I` to `i` and `İ` to `i̇`
+/// (with a combining dot). A combining dot above left behind by an earlier `to_lowercase` is removed as well.
+///
+/// # Arguments
+///
+/// * `text` - A string slice containing the text to lowercase.
+///
+/// # Returns
+///
+/// A `String` containing the lowercased text.
+pub fn turkish_lowercase(text: &str) -> String {
+    let mut lowered = String::with_capacity(text.len());
+    let mut previous = '\0';
+    for c in text.chars() {
+        match c {
+            'I' => lowered.push('ı'),
+            'İ' => lowered.push('i'),
+            '\u{0307}' if previous == 'i' || previous == 'İ' => {}
+            _ => lowered.extend(c.to_lowercase()),
+        }
+        previous = c;
+    }
+    lowered
+}
+
+/// Uppercases text following Turkish rules (`i` → `İ`, `ı` → `I`).
+///
+/// # Arguments
+///
+/// * `text` - A string slice containing the text to uppercase.
+///
+/// # Returns
+///
+/// A `String` containing the uppercased text.
+pub fn turkish_uppercase(text: &str) -> String {
+    let mut uppered = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            'i' => uppered.push('İ'),
+            'ı' => uppered.push('I'),
+            _ => uppered.extend(c.to_uppercase()),
+        }
+    }
+    uppered
+}
+
+/// Splits text into typed tokens.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::analyzer::tokenizer::{TokenKind, Tokenizer};
+///
+/// let tokens = Tokenizer::new().tokenize("IŞIK Türkiye'nin #deprem");
+/// assert_eq!(tokens[0].text, "ışık");
+/// assert_eq!(tokens[1].text, "türkiye");
+/// assert_eq!(tokens[1].suffix.as_deref(), Some("nin"));
+/// assert_eq!(tokens[2].kind, TokenKind::Hashtag);
+///
+/// let tokens = Tokenizer::new().tokenize("harika(!) :-)))");
+/// assert_eq!(tokens[1].text, "(!)");
+/// assert_eq!(tokens[2].text, ":)");
+/// assert_eq!(tokens[2].kind, TokenKind::Emoticon);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    lowercase: bool,
+    split_suffixes: bool,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Tokenizer::new()
+    }
+}
+
+impl Tokenizer {
+    /// Creates a tokenizer that lowercases with Turkish rules and splits apostrophe suffixes.
+    pub fn new() -> Self {
+        Tokenizer {
+            lowercase: true,
+            split_suffixes: true,
+        }
+    }
+
+    /// Sets whether token texts are lowercased with Turkish rules.
+    pub fn lowercase(mut self, lowercase: bool) -> Self {
+        self.lowercase = lowercase;
+        self
+    }
+
+    /// Sets whether apostrophe suffixes are split off (`Türkiye'nin` → `türkiye`).
+    /// When disabled, the apostrophe is dropped and the suffix stays attached (`türkiyenin`).
+    pub fn split_suffixes(mut self, split_suffixes: bool) -> Self {
+        self.split_suffixes = split_suffixes;
+        self
+    }
+
+    /// Splits the text into tokens in the order they appear.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string slice containing the text to tokenize.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<Token>`; punctuation and other symbols are not returned.
+    pub fn tokenize(&self, text: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut chars = text.char_indices().peekable();
+        while let Some(&(start, c)) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            let rest = &text[start..];
+            if is_url_start(rest) {
+                let end = start + url_length(rest);
+                self.push(&mut tokens, TokenKind::Url, &text[start..end], None, start);
+                skip_to(&mut chars, end);
+            } else if (c == '#' || c == '@') && rest[1..].starts_with(is_word_char) {
+                let end = start + 1 + word_length(&rest[1..]);
+                let kind = if c == '#' { TokenKind::Hashtag } else { TokenKind::Mention };
+                self.push(&mut tokens, kind, &text[start..end], None, start);
+                skip_to(&mut chars, end);
+            } else if is_emoji(c) {
+                let end = start + emoji_length(rest);
+                self.push(&mut tokens, TokenKind::Emoji, &text[start..end], None, start);
+                skip_to(&mut chars, end);
+            } else if let Some(length) = emoticon_length(rest) {
+                self.push(&mut tokens, TokenKind::Emoticon, &rest[..length], None, start);
+                skip_to(&mut chars, start + length);
+            } else if is_word_char(c) {
+                let length = word_length(rest);
+                let word = &rest[..length];
+                let kind = if word.chars().any(char::is_alphabetic) { TokenKind::Word } else { TokenKind::Number };
+                let mut end = start + length;
+                let mut suffix = None;
+                if let Some(apostrophe) = text[end..].chars().next().filter(|&a| is_apostrophe(a)) {
+                    let after = end + apostrophe.len_utf8();
+                    let suffix_length = word_length(&text[after..]);
+                    if suffix_length > 0 && text[after..after + suffix_length].chars().all(char::is_alphabetic) {
+                        suffix = Some(&text[after..after + suffix_length]);
+                        end = after + suffix_length;
+                    }
+                }
+                self.push(&mut tokens, kind, &text[start..end], suffix, start);
+                skip_to(&mut chars, end);
+            } else {
+                chars.next();
+            }
+        }
+        tokens
+    }
+
+    /// Returns the normalized texts of the tokens of the given kinds.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string slice containing the text to tokenize.
+    /// * `kinds` - The `TokenKind`s to keep.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<String>` with the normalized token texts in order.
+    pub fn terms(&self, text: &str, kinds: &[TokenKind]) -> Vec<String> {
+        self.tokenize(text)
+            .into_iter()
+            .filter(|token| kinds.contains(&token.kind))
+            .map(|token| token.text)
+            .collect()
+    }
+
+    fn push(&self, tokens: &mut Vec<Token>, kind: TokenKind, raw: &str, suffix: Option<&str>, offset: usize) {
+        let normalize = |s: &str| if self.lowercase { turkish_lowercase(s) } else { s.to_string() };
+        let (text, suffix) = match (kind, suffix) {
+            (TokenKind::Url | TokenKind::Emoji, _) => (raw.to_string(), None),
+            (TokenKind::Emoticon, _) => (normalize_emoticon(raw), None),
+            (_, Some(suffix)) if self.split_suffixes => {
+                let stem = &raw[..raw.len() - suffix.len()];
+                (normalize(stem.trim_end_matches(is_apostrophe)), Some(normalize(suffix)))
+            }
+            (_, Some(_)) => (normalize(&raw.replace(is_apostrophe, "")), None),
+            (_, None) => (normalize(raw), None),
+        };
+        tokens.push(Token { text, raw: raw.to_string(), kind, suffix, offset });
+    }
+}
+
+/// Assigns every token of `text` the number of its clause, so that phrases and modifiers
+/// do not reach across clauses. Clauses are separated by sentence punctuation,
+/// commas and contrastive conjunctions such as `ama`.
+pub(crate) fn clause_ids(text: &str, tokens: &[Token]) -> Vec<usize> {
+    let mut clause = 0;
+    let mut previous_end = 0;
+    tokens
+        .iter()
+        .map(|token| {
+            let gap = text.get(previous_end..token.offset).unwrap_or("");
+            if gap.contains(['.', ',', ';', ':', '!', '?', '\n']) || CLAUSE_BREAKS.contains(&token.text.as_str()) {
+                clause += 1;
+            }
+            previous_end = token.offset + token.raw.len();
+            clause
+        })
+        .collect()
+}
+
+fn skip_to<I: Iterator<Item = (usize, char)>>(chars: &mut std::iter::Peekable<I>, end: usize) {
+    while chars.next_if(|&(index, _)| index < end).is_some() {}
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '\u{2019}'
+}
+
+fn is_combining_mark(c: char) -> bool {
+    ('\u{0300}'..='\u{036f}').contains(&c)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || is_combining_mark(c)
+}
+
+/// Length in bytes of the word or number at the start of `text`.
+/// Digits separated by a single `.` or `,` (`3,5`, `1.000`) are kept together.
+fn word_length(text: &str) -> usize {
+    let mut end = 0;
+    let mut chars = text.char_indices().peekable();
+    let mut previous_digit = false;
+    while let Some((index, c)) = chars.next() {
+        if is_word_char(c) {
+            end = index + c.len_utf8();
+            previous_digit = c.is_ascii_digit();
+        } else if previous_digit && (c == '.' || c == ',') && chars.peek().is_some_and(|&(_, next)| next.is_ascii_digit()) {
+            previous_digit = false;
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+fn is_url_start(text: &str) -> bool {
+    ["http://", "https://", "www."]
+        .iter()
+        .any(|prefix| text.as_bytes().get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix.as_bytes())))
+}
+
+/// Length in bytes of the URL at the start of `text`, without trailing punctuation.
+fn url_length(text: &str) -> usize {
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    let url = text[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '"', '\'']);
+    url.len()
+}
+
+/// Length in bytes of the emoticon at the start of `text`, if there is one.
+/// Emoticons must not run into a word (`:Pazar` is no emoticon).
+fn emoticon_length(text: &str) -> Option<usize> {
+    let length = match FIXED_EMOTICONS.iter().find(|emoticon| text.starts_with(*emoticon)) {
+        // hearts may repeat their last digit (`<333`)
+        Some(emoticon) if emoticon.ends_with('3') => emoticon.len() + text[emoticon.len()..].chars().take_while(|&c| c == '3').count(),
+        Some(emoticon) => emoticon.len(),
+        None => {
+            let mut chars = text.chars().peekable();
+            chars.next().filter(|&eyes| eyes == ':' || eyes == ';' || eyes == '=')?;
+            let nose = usize::from(chars.next_if(|&nose| nose == '-' || nose == '\'').is_some());
+            let mouth = chars.next().filter(|&mouth| EMOTICON_MOUTHS.contains(mouth))?;
+            let repeats = 1 + std::iter::from_fn(|| chars.next_if_eq(&mouth)).count();
+            if mouth == '/' && chars.peek() == Some(&'/') {
+                return None;
+            }
+            1 + nose + repeats
+        }
+    };
+    let after = text[length..].chars().next();
+    (!after.is_some_and(char::is_alphanumeric)).then_some(length)
+}
+
+/// Drops the nose and repeated mouths of an emoticon: `:-)))` becomes `:)` and `<333` becomes `<3`.
+pub(crate) fn normalize_emoticon(raw: &str) -> String {
+    if let Some(emoticon) = FIXED_EMOTICONS.iter().find(|emoticon| raw.starts_with(*emoticon)) {
+        return emoticon.to_string();
+    }
+    let mut normalized = String::new();
+    for c in raw.chars().filter(|&c| c != '-') {
+        if !normalized.ends_with(c) {
+            normalized.push(c);
+        }
+    }
+    normalized
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c,
+        '\u{1F000}'..='\u{1FAFF}'
+        | '\u{2600}'..='\u{27BF}'
+        | '\u{2B00}'..='\u{2BFF}'
+        | '\u{2300}'..='\u{23FF}'
+        | '\u{3030}' | '\u{303D}' | '\u{3297}' | '\u{3299}' | '\u{00A9}' | '\u{00AE}' | '\u{2122}')
+}
+
+fn is_emoji_continuation(c: char) -> bool {
+    matches!(c, '\u{200D}' | '\u{FE0E}' | '\u{FE0F}' | '\u{20E3}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}')
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Length in bytes of the emoji sequence at the start of `text`.
+fn emoji_length(text: &str) -> usize {
+    let mut chars = text.char_indices();
+    let Some((_, first)) = chars.next() else { return 0 };
+    let mut end = first.len_utf8();
+    if is_regional_indicator(first) {
+        if let Some((index, second)) = chars.next().filter(|&(_, c)| is_regional_indicator(c)) {
+            end = index + second.len_utf8();
+        }
+        return end;
+    }
+    let mut after_joiner = false;
+    for (index, c) in chars {
+        if is_emoji_continuation(c) {
+            after_joiner = c == '\u{200D}';
+            end = index + c.len_utf8();
+        } else if after_joiner && is_emoji(c) {
+            after_joiner = false;
+            end = index + c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    end
+}
@@ -0,0 +1,307 @@
+//! # RustySozluk Topics
+//!
+//! Unsupervised topic discovery with Latent Dirichlet Allocation (LDA). Every entry is treated
+//! as a mixture of topics and every topic as a distribution over words; the model is fitted with
+//! collapsed Gibbs sampling on the preprocessed tokens of the entries.
+//!
+//! The result lists the most probable words of each topic and the topic distribution of each
+//! entry, which is enough to summarize a large title into a handful of themes.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use crate::analyzer::analyzer::preprocess_tokens;
+use crate::analyzer::rng::Rng;
+use crate::analyzer::stemmer::Stemmer;
+use crate::parser::Entry;
+
+/// Fits LDA topic models.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rustysozluk::analyzer::topics::Lda;
+///
+/// let texts = ["deprem yardım çadır", "yardım çadır battaniye", "maç gol hakem", "hakem penaltı gol"];
+/// let model = Lda::new(2).min_document_frequency(1).fit_texts(texts, &HashSet::new());
+/// assert_eq!(model.topics.len(), 2);
+/// assert_eq!(model.dominant_topic(0), model.dominant_topic(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Lda {
+    topics: usize,
+    iterations: usize,
+    alpha: f64,
+    beta: f64,
+    seed: u64,
+    top_words: usize,
+    min_document_frequency: usize,
+    max_document_ratio: f64,
+    stemmer: Option<Stemmer>,
+}
+
+impl Lda {
+    /// Creates a model with the given number of topics (at least 1), 200 iterations, priors
+    /// `alpha = 0.1` and `beta = 0.01`, and 10 top words per topic. Words that appear in fewer
+    /// than 2 entries or in more than half of the entries are left out.
+    pub fn new(topics: usize) -> Self {
+        Lda {
+            topics: topics.max(1),
+            iterations: 200,
+            alpha: 0.1,
+            beta: 0.01,
+            seed: 42,
+            top_words: 10,
+            min_document_frequency: 2,
+            max_document_ratio: 0.5,
+            stemmer: None,
+        }
+    }
+
+    /// Sets the number of Gibbs sampling sweeps over all tokens.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the Dirichlet prior of the topic distribution of entries; smaller values give
+    /// entries fewer topics.
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Sets the Dirichlet prior of the word distribution of topics; smaller values give
+    /// topics fewer words.
+    pub fn beta(mut self, beta: f64) -> Self {
+        self.beta = beta;
+        self
+    }
+
+    /// Sets the seed of the sampler. Results are reproducible for the same seed and input.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the number of most probable words reported per topic.
+    pub fn top_words(mut self, top_words: usize) -> Self {
+        self.top_words = top_words;
+        self
+    }
+
+    /// Sets the number of entries a word has to appear in to be modeled.
+    pub fn min_document_frequency(mut self, min_document_frequency: usize) -> Self {
+        self.min_document_frequency = min_document_frequency;
+        self
+    }
+
+    /// Sets the largest share of entries, between 0 and 1, a word may appear in to be modeled.
+    /// Words above it are too common to tell topics apart.
+    pub fn max_document_ratio(mut self, max_document_ratio: f64) -> Self {
+        self.max_document_ratio = max_document_ratio;
+        self
+    }
+
+    /// Sets the stemmer applied to every token; `None` (the default) keeps words as written.
+    pub fn stemmer(mut self, stemmer: Option<Stemmer>) -> Self {
+        self.stemmer = stemmer;
+        self
+    }
+
+    /// Fits the model on the contents of entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to model.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    ///
+    /// # Returns
+    ///
+    /// A `TopicModel` with one topic distribution per entry.
+    pub fn fit(&self, entries: &[Entry], stopwords: &HashSet<String>) -> TopicModel {
+        self.fit_texts(entries.iter().map(|entry| entry.content.as_str()), stopwords)
+    }
+
+    /// Fits the model on texts like `fit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - The texts to model.
+    /// * `stopwords` - A reference to a HashSet containing stopwords.
+    ///
+    /// # Returns
+    ///
+    /// A `TopicModel` with one topic distribution per text.
+    pub fn fit_texts<I, S>(&self, texts: I, stopwords: &HashSet<String>) -> TopicModel
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let tokenized: Vec<Vec<String>> = texts
+            .into_iter()
+            .map(|text| {
+                let tokens = preprocess_tokens(text.as_ref(), stopwords);
+                match &self.stemmer {
+                    Some(stemmer) => tokens.iter().map(|token| stemmer.stem(token)).collect(),
+                    None => tokens,
+                }
+            })
+            .collect();
+        let (vocabulary, documents) = self.vocabulary(&tokenized);
+
+        let (topics, words) = (self.topics, vocabulary.len());
+        let mut rng = Rng::new(self.seed);
+        let mut document_topic = vec![vec![0usize; topics]; documents.len()];
+        let mut topic_word = vec![vec![0usize; words]; topics];
+        let mut topic_total = vec![0usize; topics];
+        let mut assignments: Vec<Vec<usize>> = documents
+            .iter()
+            .enumerate()
+            .map(|(document, tokens)| {
+                tokens
+                    .iter()
+                    .map(|&word| {
+                        let topic = rng.below(topics);
+                        document_topic[document][topic] += 1;
+                        topic_word[topic][word] += 1;
+                        topic_total[topic] += 1;
+                        topic
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let beta_sum = self.beta * words as f64;
+        let mut weights = vec![0.0; topics];
+        for _ in 0..self.iterations {
+            for (document, tokens) in documents.iter().enumerate() {
+                for (position, &word) in tokens.iter().enumerate() {
+                    let old = assignments[document][position];
+                    document_topic[document][old] -= 1;
+                    topic_word[old][word] -= 1;
+                    topic_total[old] -= 1;
+
+                    let mut total = 0.0;
+                    for (topic, weight) in weights.iter_mut().enumerate() {
+                        *weight = (document_topic[document][topic] as f64 + self.alpha) * (topic_word[topic][word] as f64 + self.beta)
+                            / (topic_total[topic] as f64 + beta_sum);
+                        total += *weight;
+                    }
+                    let mut target = rng.next_f64() * total;
+                    let mut new = topics - 1;
+                    for (topic, weight) in weights.iter().enumerate() {
+                        if target < *weight {
+                            new = topic;
+                            break;
+                        }
+                        target -= weight;
+                    }
+
+                    assignments[document][position] = new;
+                    document_topic[document][new] += 1;
+                    topic_word[new][word] += 1;
+                    topic_total[new] += 1;
+                }
+            }
+        }
+
+        let token_count: usize = topic_total.iter().sum();
+        let topic_list = (0..topics)
+            .map(|topic| {
+                let mut ranked: Vec<(String, f64)> = (0..words)
+                    .map(|word| (vocabulary[word].clone(), (topic_word[topic][word] as f64 + self.beta) / (topic_total[topic] as f64 + beta_sum)))
+                    .collect();
+                ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                ranked.truncate(self.top_words);
+                Topic {
+                    id: topic,
+                    words: ranked,
+                    weight: if token_count == 0 { 0.0 } else { topic_total[topic] as f64 / token_count as f64 },
+                }
+            })
+            .collect();
+        let alpha_sum = self.alpha * topics as f64;
+        let distributions = documents
+            .iter()
+            .zip(&document_topic)
+            .map(|(tokens, counts)| counts.iter().map(|&count| (count as f64 + self.alpha) / (tokens.len() as f64 + alpha_sum)).collect())
+            .collect();
+        TopicModel { topics: topic_list, documents: distributions, vocabulary_size: words }
+    }
+
+    /// Builds the vocabulary from the document frequency limits and maps documents to word ids.
+    fn vocabulary(&self, tokenized: &[Vec<String>]) -> (Vec<String>, Vec<Vec<usize>>) {
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        for tokens in tokenized {
+            let unique: HashSet<&str> = tokens.iter().map(String::as_str).collect();
+            for word in unique {
+                *document_frequency.entry(word).or_insert(0) += 1;
+            }
+        }
+        let max_documents = (self.max_document_ratio * tokenized.len() as f64).max(1.0);
+        let mut vocabulary: Vec<String> = document_frequency
+            .into_iter()
+            .filter(|&(_, frequency)| frequency >= self.min_document_frequency && frequency as f64 <= max_documents)
+            .map(|(word, _)| word.to_string())
+            .collect();
+        vocabulary.sort();
+        let ids: HashMap<&str, usize> = vocabulary.iter().enumerate().map(|(id, word)| (word.as_str(), id)).collect();
+        let documents = tokenized
+            .iter()
+            .map(|tokens| tokens.iter().filter_map(|token| ids.get(token.as_str()).copied()).collect())
+            .collect();
+        (vocabulary, documents)
+    }
+}
+
+/// A topic found by the model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Topic {
+    /// Index of the topic, matching the positions in `TopicModel::documents`.
+    pub id: usize,
+    /// The most probable words of the topic with their probabilities.
+    pub words: Vec<(String, f64)>,
+    /// Share of all modeled tokens assigned to the topic.
+    pub weight: f64,
+}
+
+/// A fitted topic model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TopicModel {
+    /// The topics, in index order.
+    pub topics: Vec<Topic>,
+    /// The topic distribution of every entry, in input order; each distribution sums to 1.
+    pub documents: Vec<Vec<f64>>,
+    /// Number of distinct words that were modeled.
+    pub vocabulary_size: usize,
+}
+
+impl TopicModel {
+    /// Returns the most probable topic of an entry, `None` if the index is out of range.
+    pub fn dominant_topic(&self, document: usize) -> Option<usize> {
+        let distribution = self.documents.get(document)?;
+        distribution.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(&a.0))).map(|(topic, _)| topic)
+    }
+
+    /// Returns the entries whose dominant topic is `topic`, most representative first.
+    pub fn documents_for(&self, topic: usize) -> Vec<(usize, f64)> {
+        let mut documents: Vec<(usize, f64)> = (0..self.documents.len())
+            .filter(|&document| self.dominant_topic(document) == Some(topic))
+            .map(|document| (document, self.documents[document][topic]))
+            .collect();
+        documents.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        documents
+    }
+}
+
+impl fmt::Display for TopicModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for topic in &self.topics {
+            let words: Vec<&str> = topic.words.iter().map(|(word, _)| word.as_str()).collect();
+            writeln!(f, "Topic {} ({:.1}%): {}", topic.id + 1, topic.weight * 100.0, words.join(", "))?;
+        }
+        Ok(())
+    }
+}
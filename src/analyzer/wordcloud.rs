@@ -0,0 +1,604 @@
+//! # RustySozluk Word Cloud
+//!
+//! Renders word frequencies, e.g. from `word_frequencies` or `top_words`, as a word cloud.
+//!
+//! Words are placed from the most to the least frequent, each one on the first free spot along a
+//! spiral from the center of the canvas, shrinking when there is no room left. Free space is
+//! tracked on a grid, which can be limited to a shape with a `Mask`.
+//!
+//! SVG output is always available and leaves the glyphs to the viewer, which picks the first
+//! installed font of `font_family`. With the `png` feature, a TrueType/OpenType font can be loaded
+//! to measure words exactly and to render PNG images.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use crate::analyzer::rng::Rng;
+use crate::util::escape_html;
+
+/// Side length in pixels of a cell of the occupancy grid.
+const CELL: u32 = 2;
+
+/// Turkish letters a font has to cover to be used for a word cloud.
+#[cfg(feature = "png")]
+const TURKISH_LETTERS: &str = "çÇğĞıİöÖşŞüÜ";
+
+/// The shape words are placed in.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Mask {
+    /// The whole canvas.
+    #[default]
+    Rectangle,
+    /// The largest ellipse that fits the canvas.
+    Ellipse,
+    /// A bitmap stretched to the canvas; words are only placed on `true` pixels.
+    Bitmap {
+        width: u32,
+        height: u32,
+        /// Row-major pixels, `width * height` of them; missing pixels are treated as empty.
+        pixels: Vec<bool>,
+    },
+}
+
+impl Mask {
+    /// Builds a bitmap mask from ASCII art, where `#` marks the drawable area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustysozluk::analyzer::wordcloud::Mask;
+    ///
+    /// let heart = Mask::from_ascii(" ## ## \n#######\n ##### \n  ###  \n   #   ");
+    /// ```
+    pub fn from_ascii(art: &str) -> Mask {
+        let rows: Vec<Vec<char>> = art.lines().map(|line| line.chars().collect()).collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let pixels = rows
+            .iter()
+            .flat_map(|row| (0..width).map(move |x| row.get(x) == Some(&'#')))
+            .collect();
+        Mask::Bitmap { width: width as u32, height: rows.len() as u32, pixels }
+    }
+
+    /// Loads a bitmap mask from a PNG image. Dark, opaque pixels are drawable; white or
+    /// transparent pixels stay empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - A `&str` specifying the path of the PNG image.
+    #[cfg(feature = "png")]
+    pub fn from_png(file_name: &str) -> io::Result<Mask> {
+        let mut decoder = png::Decoder::new(io::BufReader::new(File::open(file_name)?));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let channels = info.color_type.samples();
+        let pixels = buffer[..info.buffer_size()]
+            .chunks(channels)
+            .map(|pixel| {
+                let (luma, alpha) = match pixel {
+                    [gray] => (*gray as u32, 255),
+                    [gray, alpha] => (*gray as u32, *alpha),
+                    [r, g, b] => ((*r as u32 * 299 + *g as u32 * 587 + *b as u32 * 114) / 1000, 255),
+                    [r, g, b, alpha, ..] => ((*r as u32 * 299 + *g as u32 * 587 + *b as u32 * 114) / 1000, *alpha),
+                    [] => (255, 0),
+                };
+                luma < 128 && alpha >= 128
+            })
+            .collect();
+        Ok(Mask::Bitmap { width: info.width, height: info.height, pixels })
+    }
+
+    /// Returns whether the point at `(x, y)` of a `width` × `height` canvas is drawable.
+    fn allows(&self, x: f64, y: f64, width: f64, height: f64) -> bool {
+        match self {
+            Mask::Rectangle => true,
+            Mask::Ellipse => {
+                let (dx, dy) = (x / width * 2.0 - 1.0, y / height * 2.0 - 1.0);
+                dx * dx + dy * dy <= 1.0
+            }
+            Mask::Bitmap { width: mask_width, height: mask_height, pixels } => {
+                let mx = (x / width * *mask_width as f64) as usize;
+                let my = (y / height * *mask_height as f64) as usize;
+                // missing pixels (a `pixels` vector shorter than `width * height`) stay empty
+                mx < *mask_width as usize && my < *mask_height as usize && pixels.get(my * *mask_width as usize + mx).copied().unwrap_or(false)
+            }
+        }
+    }
+}
+
+/// A font loaded from a TrueType or OpenType file.
+#[cfg(feature = "png")]
+#[derive(Clone)]
+pub struct CloudFont {
+    font: ab_glyph::FontArc,
+}
+
+#[cfg(feature = "png")]
+impl std::fmt::Debug for CloudFont {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CloudFont").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "png")]
+impl CloudFont {
+    /// Loads a font file such as `DejaVuSans.ttf` or `NotoSans-Regular.ttf`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - A `&str` specifying the path of the font file.
+    ///
+    /// # Returns
+    ///
+    /// The font, or an `InvalidData` error if the file is not a font or lacks Turkish letters.
+    pub fn from_file(file_name: &str) -> io::Result<CloudFont> {
+        CloudFont::from_bytes(std::fs::read(file_name)?)
+    }
+
+    /// Loads a font from the bytes of a font file like `from_file`.
+    pub fn from_bytes(bytes: Vec<u8>) -> io::Result<CloudFont> {
+        use ab_glyph::Font;
+
+        let font = ab_glyph::FontArc::try_from_vec(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(letter) = TURKISH_LETTERS.chars().find(|&letter| font.glyph_id(letter).0 == 0) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("the font has no glyph for '{}'", letter)));
+        }
+        Ok(CloudFont { font })
+    }
+
+    fn scaled(&self, font_size: f32) -> ab_glyph::PxScaleFont<&ab_glyph::FontArc> {
+        use ab_glyph::Font;
+
+        let units_per_em = self.font.units_per_em().unwrap_or(1000.0);
+        self.font.as_scaled(font_size * self.font.height_unscaled() / units_per_em)
+    }
+
+    fn measure(&self, text: &str, font_size: f32) -> (f32, f32, f32) {
+        use ab_glyph::{Font, ScaleFont};
+
+        let font = self.scaled(font_size);
+        let mut width = 0.0;
+        let mut previous = None;
+        for c in text.chars() {
+            let id = self.font.glyph_id(c);
+            if let Some(previous) = previous {
+                width += font.kern(previous, id);
+            }
+            width += font.h_advance(id);
+            previous = Some(id);
+        }
+        (width, font.ascent(), -font.descent())
+    }
+}
+
+/// Options for `WordCloud::build`.
+#[derive(Debug, Clone)]
+pub struct WordCloudOptions {
+    /// Width of the canvas in pixels.
+    pub width: u32,
+    /// Height of the canvas in pixels.
+    pub height: u32,
+    /// Number of most frequent words to place.
+    pub max_words: usize,
+    /// Font size of the most frequent word, before shrinking to make it fit.
+    pub max_font_size: f32,
+    /// Smallest font size; placing stops at the first word that does not fit at this size.
+    pub min_font_size: f32,
+    /// How much font sizes follow frequencies, between 0 (every word as large as the previous
+    /// one would fit) and 1 (sizes proportional to frequencies).
+    pub relative_scaling: f32,
+    /// Share of the words, between 0 and 1, that are written vertically.
+    pub vertical_ratio: f64,
+    /// Colors of the words as `#rrggbb`, picked at random for every word.
+    pub colors: Vec<String>,
+    /// Background color as `#rrggbb`, `None` for a transparent background.
+    pub background: Option<String>,
+    /// CSS font family of the SVG text. Choose fonts with Turkish letters.
+    pub font_family: String,
+    /// Font used to measure words and to render PNG images; without it, widths are estimated.
+    #[cfg(feature = "png")]
+    pub font: Option<CloudFont>,
+    /// The shape words are placed in.
+    pub mask: Mask,
+    /// Seed of the color and orientation choices. Results are reproducible for the same seed.
+    pub seed: u64,
+}
+
+impl Default for WordCloudOptions {
+    fn default() -> Self {
+        WordCloudOptions {
+            width: 800,
+            height: 600,
+            max_words: 200,
+            max_font_size: 96.0,
+            min_font_size: 8.0,
+            relative_scaling: 0.5,
+            vertical_ratio: 0.1,
+            colors: ["#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#17becf"]
+                .iter()
+                .map(|color| color.to_string())
+                .collect(),
+            background: Some("#ffffff".to_string()),
+            font_family: "DejaVu Sans, Noto Sans, Arial, sans-serif".to_string(),
+            #[cfg(feature = "png")]
+            font: None,
+            mask: Mask::Rectangle,
+            seed: 42,
+        }
+    }
+}
+
+/// A word placed on the canvas.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlacedWord {
+    pub text: String,
+    pub frequency: usize,
+    /// Font size (em size) in pixels.
+    pub font_size: f32,
+    /// Left edge of the bounding box of the word.
+    pub x: f32,
+    /// Top edge of the bounding box of the word.
+    pub y: f32,
+    /// Width of the bounding box; the text height for vertical words.
+    pub width: f32,
+    /// Height of the bounding box; the text length for vertical words.
+    pub height: f32,
+    /// Distance from the top of the text to its baseline.
+    pub ascent: f32,
+    /// Whether the word is rotated to read bottom to top.
+    pub vertical: bool,
+    pub color: String,
+}
+
+/// A laid out word cloud.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rustysozluk::analyzer::wordcloud::{WordCloud, WordCloudOptions};
+///
+/// let frequencies = HashMap::from([("deprem".to_string(), 40), ("yardım".to_string(), 25), ("çadır".to_string(), 10)]);
+/// let cloud = WordCloud::build(&frequencies, &WordCloudOptions::default());
+/// assert_eq!(cloud.words[0].text, "deprem");
+/// assert!(cloud.to_svg().contains("çadır"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WordCloud {
+    pub width: u32,
+    pub height: u32,
+    /// The placed words, most frequent first.
+    pub words: Vec<PlacedWord>,
+    background: Option<String>,
+    font_family: String,
+    #[cfg(feature = "png")]
+    font: Option<CloudFont>,
+}
+
+impl WordCloud {
+    /// Lays out a word cloud from a frequency map such as the one of `word_frequencies`.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequencies` - Words and their frequencies.
+    /// * `options` - The size, shape, colors and fonts of the cloud.
+    ///
+    /// # Returns
+    ///
+    /// A `WordCloud` with the words that fit, most frequent first.
+    pub fn build(frequencies: &HashMap<String, usize>, options: &WordCloudOptions) -> WordCloud {
+        let mut ranked: Vec<(String, usize)> = frequencies.iter().map(|(word, &frequency)| (word.clone(), frequency)).collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        WordCloud::from_ranked(&ranked, options)
+    }
+
+    /// Lays out a word cloud from ranked words such as the result of `top_words`.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - Words and their frequencies, most frequent first.
+    /// * `options` - The size, shape, colors and fonts of the cloud.
+    ///
+    /// # Returns
+    ///
+    /// A `WordCloud` with the words that fit, most frequent first.
+    pub fn from_ranked(words: &[(String, usize)], options: &WordCloudOptions) -> WordCloud {
+        let mut grid = Grid::new(options.width, options.height, &options.mask);
+        let mut rng = Rng::new(options.seed);
+        let scaling = options.relative_scaling.clamp(0.0, 1.0);
+
+        let mut placed: Vec<PlacedWord> = Vec::new();
+        'words: for (text, frequency) in words.iter().filter(|(_, frequency)| *frequency > 0).take(options.max_words) {
+            let vertical = rng.next_f64() < options.vertical_ratio;
+            let color = match options.colors.len() {
+                0 => "#000000".to_string(),
+                colors => options.colors[rng.below(colors)].clone(),
+            };
+            // Sizes follow the frequency ratio to the previous word, starting from its actual size.
+            let mut font_size = match placed.last() {
+                Some(previous) => previous.font_size * (scaling * *frequency as f32 / previous.frequency as f32 + (1.0 - scaling)),
+                None => options.max_font_size,
+            };
+            while font_size >= options.min_font_size {
+                let (length, ascent, descent) = measure(options, text, font_size);
+                let (width, height) = if vertical { (ascent + descent, length) } else { (length, ascent + descent) };
+                if let Some((x, y)) = grid.find(width, height) {
+                    grid.occupy(x, y, width, height);
+                    placed.push(PlacedWord {
+                        text: text.clone(),
+                        frequency: *frequency,
+                        font_size,
+                        x,
+                        y,
+                        width,
+                        height,
+                        ascent,
+                        vertical,
+                        color,
+                    });
+                    continue 'words;
+                }
+                font_size *= 0.9;
+            }
+            break;
+        }
+
+        WordCloud {
+            width: options.width,
+            height: options.height,
+            words: placed,
+            background: options.background.clone(),
+            font_family: options.font_family.clone(),
+            #[cfg(feature = "png")]
+            font: options.font.clone(),
+        }
+    }
+
+    /// Renders the word cloud as an SVG document.
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            self.width, self.height
+        );
+        if let Some(background) = &self.background {
+            svg.push_str(&format!("  <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n", escape_html(background)));
+        }
+        svg.push_str(&format!("  <g font-family=\"{}\">\n", escape_html(&self.font_family)));
+        for word in &self.words {
+            let position = if word.vertical {
+                format!("transform=\"translate({:.1},{:.1}) rotate(-90)\" x=\"0\" y=\"{:.1}\"", word.x, word.y + word.height, word.ascent)
+            } else {
+                format!("x=\"{:.1}\" y=\"{:.1}\"", word.x, word.y + word.ascent)
+            };
+            svg.push_str(&format!(
+                "    <text {} font-size=\"{:.1}\" fill=\"{}\">{}</text>\n",
+                position,
+                word.font_size,
+                escape_html(&word.color),
+                escape_html(&word.text)
+            ));
+        }
+        svg.push_str("  </g>\n</svg>\n");
+        svg
+    }
+
+    /// Writes the word cloud as SVG.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination implementing `Write`.
+    pub fn write_svg<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_svg().as_bytes())
+    }
+
+    /// Exports the word cloud to an SVG file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - A `&str` specifying the path of the SVG file to create.
+    pub fn export_to_svg(&self, file_name: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(file_name)?);
+        self.write_svg(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Renders the word cloud as a PNG image with the font of the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination implementing `Write`.
+    ///
+    /// # Returns
+    ///
+    /// An `InvalidInput` error if the cloud was built without a font.
+    #[cfg(feature = "png")]
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        use ab_glyph::{point, Font, ScaleFont};
+
+        let Some(font) = &self.font else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "a font is required for PNG output"));
+        };
+        let (width, height) = (self.width as usize, self.height as usize);
+        let background = self.background.as_deref().and_then(parse_color).map_or([0.0; 4], |[r, g, b]| [r, g, b, 1.0]);
+        let mut canvas = vec![background; width * height];
+
+        for word in &self.words {
+            let color = parse_color(&word.color).unwrap_or([0.0; 3]);
+            let scaled = font.scaled(word.font_size);
+            let mut caret = 0.0;
+            let mut previous = None;
+            for c in word.text.chars() {
+                let id = font.font.glyph_id(c);
+                if let Some(previous) = previous {
+                    caret += scaled.kern(previous, id);
+                }
+                previous = Some(id);
+                let glyph = id.with_scale_and_position(scaled.scale(), point(caret, word.ascent));
+                caret += scaled.h_advance(id);
+                let Some(outline) = font.font.outline_glyph(glyph) else { continue };
+                let bounds = outline.px_bounds();
+                outline.draw(|gx, gy, coverage| {
+                    // Text-local coordinates, rotated for vertical words like the SVG transform.
+                    let (u, v) = (bounds.min.x + gx as f32, bounds.min.y + gy as f32);
+                    let (x, y) = if word.vertical { (word.x + v, word.y + word.height - u) } else { (word.x + u, word.y + v) };
+                    if x < 0.0 || y < 0.0 || x as usize >= width || y as usize >= height {
+                        return;
+                    }
+                    let pixel = &mut canvas[y as usize * width + x as usize];
+                    let alpha = coverage.clamp(0.0, 1.0);
+                    let out_alpha = alpha + pixel[3] * (1.0 - alpha);
+                    if out_alpha > 0.0 {
+                        for channel in 0..3 {
+                            pixel[channel] = (color[channel] * alpha + pixel[channel] * pixel[3] * (1.0 - alpha)) / out_alpha;
+                        }
+                    }
+                    pixel[3] = out_alpha;
+                });
+            }
+        }
+
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let data: Vec<u8> = canvas.iter().flat_map(|pixel| pixel.map(|channel| (channel * 255.0).round() as u8)).collect();
+        encoder
+            .write_header()
+            .and_then(|mut png_writer| png_writer.write_image_data(&data))
+            .map_err(io::Error::other)
+    }
+
+    /// Exports the word cloud to a PNG file like `write_png`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - A `&str` specifying the path of the PNG file to create.
+    #[cfg(feature = "png")]
+    pub fn export_to_png(&self, file_name: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(file_name)?);
+        self.write_png(&mut writer)?;
+        writer.flush()
+    }
+}
+
+/// Returns the length, ascent and descent of a word in pixels.
+fn measure(options: &WordCloudOptions, text: &str, font_size: f32) -> (f32, f32, f32) {
+    #[cfg(feature = "png")]
+    if let Some(font) = &options.font {
+        return font.measure(text, font_size);
+    }
+    let _ = options;
+    // Average advance widths of a sans-serif font, in ems.
+    let length: f32 = text
+        .chars()
+        .map(|c| match c {
+            'i' | 'ı' | 'l' | 'j' | 'İ' | 'I' | '\'' | '!' | '.' | ',' | ':' | ';' | '|' => 0.3,
+            'f' | 't' | 'r' => 0.4,
+            'm' | 'w' | 'M' | 'W' => 0.9,
+            c if c.is_uppercase() => 0.7,
+            _ => 0.6,
+        })
+        .sum();
+    (length * font_size, 0.93 * font_size, 0.24 * font_size)
+}
+
+/// Parses a `#rrggbb` or `#rgb` color into channels between 0 and 1.
+#[cfg(feature = "png")]
+fn parse_color(color: &str) -> Option<[f32; 3]> {
+    let hex = color.strip_prefix('#')?;
+    let digits: Vec<u32> = hex.chars().map(|c| c.to_digit(16)).collect::<Option<_>>()?;
+    let channels = match digits[..] {
+        [r, g, b] => [r * 17, g * 17, b * 17],
+        [r1, r2, g1, g2, b1, b2] => [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2],
+        _ => return None,
+    };
+    Some(channels.map(|channel| channel as f32 / 255.0))
+}
+
+/// Occupancy of the canvas in cells of `CELL` pixels, with a summed-area table for constant-time
+/// rectangle checks.
+struct Grid {
+    columns: usize,
+    rows: usize,
+    occupied: Vec<bool>,
+    sums: Vec<u32>,
+}
+
+impl Grid {
+    fn new(width: u32, height: u32, mask: &Mask) -> Grid {
+        let (columns, rows) = (width.div_ceil(CELL) as usize, height.div_ceil(CELL) as usize);
+        let occupied = (0..rows * columns)
+            .map(|cell| {
+                let (x, y) = ((cell % columns) as f64 + 0.5, (cell / columns) as f64 + 0.5);
+                !mask.allows(x * CELL as f64, y * CELL as f64, width as f64, height as f64)
+            })
+            .collect();
+        let mut grid = Grid { columns, rows, occupied, sums: Vec::new() };
+        grid.summarize();
+        grid
+    }
+
+    fn summarize(&mut self) {
+        let stride = self.columns + 1;
+        self.sums = vec![0; stride * (self.rows + 1)];
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                self.sums[(row + 1) * stride + column + 1] = u32::from(self.occupied[row * self.columns + column])
+                    + self.sums[row * stride + column + 1]
+                    + self.sums[(row + 1) * stride + column]
+                    - self.sums[row * stride + column];
+            }
+        }
+    }
+
+    /// Returns the cells covered by a pixel rectangle, `None` if it leaves the canvas.
+    fn cells(&self, x: f32, y: f32, width: f32, height: f32) -> Option<(usize, usize, usize, usize)> {
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        let (left, top) = ((x / CELL as f32) as usize, (y / CELL as f32) as usize);
+        let (right, bottom) = (((x + width) / CELL as f32).ceil() as usize, ((y + height) / CELL as f32).ceil() as usize);
+        (right <= self.columns && bottom <= self.rows).then_some((left, top, right, bottom))
+    }
+
+    fn is_free(&self, (left, top, right, bottom): (usize, usize, usize, usize)) -> bool {
+        let stride = self.columns + 1;
+        self.sums[bottom * stride + right] + self.sums[top * stride + left] == self.sums[top * stride + right] + self.sums[bottom * stride + left]
+    }
+
+    /// Returns the top-left corner of the first free spot for a rectangle along a spiral from the
+    /// center of the canvas.
+    fn find(&self, width: f32, height: f32) -> Option<(f32, f32)> {
+        let (center_x, center_y) = (self.columns as f32 * CELL as f32 / 2.0, self.rows as f32 * CELL as f32 / 2.0);
+        let aspect = self.columns as f32 / self.rows.max(1) as f32;
+        let max_radius = (center_x * center_x + center_y * center_y).sqrt() + width.max(height);
+        let (mut angle, mut radius) = (0.0f32, 0.0f32);
+        while radius <= max_radius {
+            let x = center_x + radius * angle.cos() * aspect.max(1.0) - width / 2.0;
+            let y = center_y + radius * angle.sin() / aspect.min(1.0) - height / 2.0;
+            if let Some(cells) = self.cells(x, y, width, height) {
+                if self.is_free(cells) {
+                    return Some((x, y));
+                }
+            }
+            // Advance about one cell along the spiral.
+            let step = (CELL as f32 / radius.max(CELL as f32)).min(0.5);
+            angle += step;
+            radius = 1.5 * angle;
+        }
+        None
+    }
+
+    fn occupy(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        if let Some((left, top, right, bottom)) = self.cells(x, y, width, height) {
+            for row in top..bottom {
+                for column in left..right {
+                    self.occupied[row * self.columns + column] = true;
+                }
+            }
+            self.summarize();
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! # RustySozluk Columnar Exporter
+//!
+//! Exports entries into Apache Arrow and Apache Parquet with a typed schema, so that tools such as
+//! Polars and DuckDB can load them without guessing column types.
+//!
+//! The Arrow parts are available with the `arrow` cargo feature and the Parquet parts with the `parquet` feature.
+//!
+//! | Column       | Type                                | Nullable |
+//! |--------------|-------------------------------------|----------|
+//! | `id`         | `Int64`                             | no       |
+//! | `title`      | `Utf8`                              | no       |
+//! | `username`   | `Utf8`                              | no       |
+//! | `content`    | `Utf8`                              | no       |
+//! | `date`       | `Utf8` (the date text as scraped)   | no       |
+//! | `created_at` | `Timestamp(Millisecond)`            | yes      |
+//! | `edited_at`  | `Timestamp(Millisecond)`            | yes      |
+//! | `favorites`  | `Int32`                             | no       |
+//!
+//! Timestamps carry no timezone: they hold the site's local wall-clock time, exactly as written in `Entry::date`.
+
+use arrow_array::{ArrayRef, Int32Array, Int64Array, RecordBatch, StringArray, TimestampMillisecondArray};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use std::fs::File;
+use std::io::Error;
+#[cfg(feature = "parquet")]
+use std::io::Write;
+use std::sync::Arc;
+use crate::parser::{parse_entry_date, Entry};
+
+/// Returns the Arrow schema used for exported entries.
+pub fn entry_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("username", DataType::Utf8, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("date", DataType::Utf8, false),
+        Field::new("created_at", DataType::Timestamp(TimeUnit::Millisecond, None), true),
+        Field::new("edited_at", DataType::Timestamp(TimeUnit::Millisecond, None), true),
+        Field::new("favorites", DataType::Int32, false),
+    ]))
+}
+
+/// Converts entries into an Arrow `RecordBatch` following `entry_schema`.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` to convert.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(RecordBatch)` - One row per entry.
+/// * `Err(ArrowError)` - If the columns could not be assembled.
+pub fn entries_to_record_batch(entries: &[Entry]) -> Result<RecordBatch, ArrowError> {
+    let dates: Vec<_> = entries.iter().map(|e| parse_entry_date(&e.date)).collect();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from_iter_values(entries.iter().map(|e| e.id as i64))),
+        Arc::new(StringArray::from_iter_values(entries.iter().map(|e| &e.title))),
+        Arc::new(StringArray::from_iter_values(entries.iter().map(|e| &e.username))),
+        Arc::new(StringArray::from_iter_values(entries.iter().map(|e| &e.content))),
+        Arc::new(StringArray::from_iter_values(entries.iter().map(|e| &e.date))),
+        Arc::new(TimestampMillisecondArray::from_iter(
+            dates.iter().map(|d| d.map(|(created, _)| created.and_utc().timestamp_millis())),
+        )),
+        Arc::new(TimestampMillisecondArray::from_iter(
+            dates.iter().map(|d| d.and_then(|(_, edited)| edited).map(|t| t.and_utc().timestamp_millis())),
+        )),
+        Arc::new(Int32Array::from_iter_values(entries.iter().map(|e| e.favorites.min(i32::MAX as u32) as i32))),
+    ];
+    RecordBatch::try_new(entry_schema(), columns)
+}
+
+/// Exports a list of entries to an Arrow IPC (Feather v2) file.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the Arrow file to be created.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO or Arrow error occurred during export.
+pub fn export_to_arrow(entries: Vec<Entry>, file_name: &str) -> Result<(), Error> {
+    let file = File::create(file_name)?;
+    let batch = entries_to_record_batch(&entries).map_err(Error::other)?;
+    let mut writer = arrow_ipc::writer::FileWriter::try_new(file, &entry_schema()).map_err(Error::other)?;
+    writer.write(&batch).map_err(Error::other)?;
+    writer.finish().map_err(Error::other)
+}
+
+/// Options controlling how Parquet files are written.
+#[cfg(feature = "parquet")]
+#[derive(Debug, Clone)]
+pub struct ParquetOptions {
+    /// Maximum number of rows per row group. Entries are also converted in chunks of this size,
+    /// so large datasets never need to be held as a single Arrow batch.
+    pub row_group_size: usize,
+    /// Compression codec applied to every column.
+    pub compression: parquet::basic::Compression,
+}
+
+#[cfg(feature = "parquet")]
+impl Default for ParquetOptions {
+    fn default() -> Self {
+        ParquetOptions {
+            row_group_size: 64 * 1024,
+            compression: parquet::basic::Compression::SNAPPY,
+        }
+    }
+}
+
+/// Exports a list of entries to a Parquet file using the default `ParquetOptions`.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the Parquet file to be created.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO or Parquet error occurred during export.
+#[cfg(feature = "parquet")]
+pub fn export_to_parquet(entries: Vec<Entry>, file_name: &str) -> Result<(), Error> {
+    let file = File::create(file_name)?;
+    write_parquet(&entries, file, &ParquetOptions::default())
+}
+
+/// Writes entries as Parquet to any writer, one row group per `options.row_group_size` entries.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` to be written.
+/// * `writer` - The destination implementing `Write + Send`.
+/// * `options` - Row group size and compression settings.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If all entries were written.
+/// * `Err(Error)` - If an IO or Parquet error occurred.
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: Write + Send>(entries: &[Entry], writer: W, options: &ParquetOptions) -> Result<(), Error> {
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+
+    let row_group_size = options.row_group_size.max(1);
+    let properties = WriterProperties::builder()
+        .set_max_row_group_size(row_group_size)
+        .set_compression(options.compression)
+        .build();
+    let mut writer = ArrowWriter::try_new(writer, entry_schema(), Some(properties)).map_err(Error::other)?;
+    for chunk in entries.chunks(row_group_size) {
+        let batch = entries_to_record_batch(chunk).map_err(Error::other)?;
+        writer.write(&batch).map_err(Error::other)?;
+        writer.flush().map_err(Error::other)?;
+    }
+    writer.close().map_err(Error::other)?;
+    Ok(())
+}
@@ -0,0 +1,416 @@
+//! # RustySozluk Digest Exporter
+//!
+//! Renders entries as human-readable archives: Markdown, a self-contained HTML page, or an EPUB
+//! book for e-readers (with the `epub` cargo feature).
+//!
+//! Entries are grouped by title in the order the titles first appear, and every title is split
+//! into pages of `DigestOptions::entries_per_page` entries, mirroring the pages of the site.
+//! Line breaks in the content are kept and `(bkz: ...)` references are turned into links.
+
+use std::fs::File;
+use std::io::{Error, Write};
+use crate::parser::{bkz_references, Entry};
+use crate::util::escape_html;
+
+const SITE_URL: &str = "https://eksisozluk.com";
+
+/// Options controlling how a digest is rendered.
+#[derive(Debug, Clone)]
+pub struct DigestOptions {
+    /// Heading of the whole document, also used as the book title for EPUB.
+    pub heading: String,
+    /// Number of entries per page inside a title. `0` puts every entry of a title on one page.
+    pub entries_per_page: usize,
+}
+
+impl Default for DigestOptions {
+    fn default() -> Self {
+        DigestOptions {
+            heading: "Eksi Sozluk Digest".to_string(),
+            entries_per_page: 10,
+        }
+    }
+}
+
+/// Entries of one title, in their original order.
+struct TitleGroup<'a> {
+    title: &'a str,
+    entries: Vec<&'a Entry>,
+}
+
+impl<'a> TitleGroup<'a> {
+    /// Splits the entries of the title into pages.
+    fn pages(&self, options: &DigestOptions) -> std::slice::Chunks<'_, &'a Entry> {
+        let size = if options.entries_per_page == 0 { self.entries.len().max(1) } else { options.entries_per_page };
+        self.entries.chunks(size)
+    }
+}
+
+/// Groups entries by title, keeping the order in which titles first appear.
+fn group_by_title(entries: &[Entry]) -> Vec<TitleGroup<'_>> {
+    let mut groups: Vec<TitleGroup> = Vec::new();
+    for entry in entries {
+        match groups.iter_mut().find(|group| group.title == entry.title) {
+            Some(group) => group.entries.push(entry),
+            None => groups.push(TitleGroup { title: &entry.title, entries: vec![entry] }),
+        }
+    }
+    groups
+}
+
+fn display_title(title: &str) -> &str {
+    if title.is_empty() { "(untitled)" } else { title }
+}
+
+fn title_url(title: &str) -> String {
+    reqwest::Url::parse_with_params(&format!("{}/", SITE_URL), &[("q", title)])
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| SITE_URL.to_string())
+}
+
+fn entry_url(id: u64) -> String {
+    format!("{}/entry/{}", SITE_URL, id)
+}
+
+fn reference_url(target: &str) -> String {
+    match target.strip_prefix('#').and_then(|id| id.parse::<u64>().ok()) {
+        Some(id) => entry_url(id),
+        None => title_url(target),
+    }
+}
+
+/// Splits content into plain text and `(bkz: ...)` link targets, in order.
+fn content_segments(content: &str) -> Vec<(&str, Option<&str>)> {
+    let mut segments = Vec::new();
+    let mut position = 0;
+    for reference in bkz_references(content) {
+        segments.push((&content[position..reference.range.start], None));
+        segments.push((&content[reference.range.clone()], Some(&content[reference.range.clone()])));
+        position = reference.range.end;
+    }
+    segments.push((&content[position..], None));
+    segments
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for line in text.split('\n') {
+        if !escaped.is_empty() {
+            escaped.push_str("  \n");
+        }
+        // leading whitespace would turn the line into a code block or hide a list marker
+        let line = line.trim_start();
+        if line.starts_with(['-', '+', '=']) {
+            escaped.push('\\');
+        }
+        let list_marker = ordered_list_marker(line);
+        for (index, c) in line.char_indices() {
+            if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|') || Some(index) == list_marker {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Returns the byte index of the `.` or `)` of an ordered list marker (`1. `, `2) `) at the start of a line.
+fn ordered_list_marker(line: &str) -> Option<usize> {
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    let rest = &line.as_bytes()[digits..];
+    (digits > 0 && matches!(rest.first(), Some(b'.' | b')')) && matches!(rest.get(1), None | Some(b' ' | b'\t'))).then_some(digits)
+}
+
+fn content_to_markdown(content: &str) -> String {
+    content_segments(content)
+        .into_iter()
+        .map(|(text, target)| match target {
+            Some(target) => format!("[{}]({})", escape_markdown(text), reference_url(target)),
+            None => escape_markdown(text),
+        })
+        .collect()
+}
+
+/// Renders content as XHTML-compatible markup, shared by the HTML and EPUB exporters.
+fn content_to_html(content: &str) -> String {
+    content_segments(content)
+        .into_iter()
+        .map(|(text, target)| {
+            let text = escape_html(text).replace('\n', "<br/>");
+            match target {
+                Some(target) => format!("<a href=\"{}\">{}</a>", escape_html(&reference_url(target)), text),
+                None => text,
+            }
+        })
+        .collect()
+}
+
+/// Renders the footer of an entry (author, date and permalink) as HTML.
+fn entry_footer_html(entry: &Entry) -> String {
+    let date = if entry.id == 0 {
+        escape_html(&entry.date)
+    } else {
+        format!("<a href=\"{}\">{}</a>", entry_url(entry.id), escape_html(&entry.date))
+    };
+    let parts: Vec<String> = [escape_html(&entry.username), date].into_iter().filter(|p| !p.is_empty()).collect();
+    format!("<footer>{}</footer>", parts.join(" &middot; "))
+}
+
+/// Renders entries as a Markdown document.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` to render.
+/// * `options` - The `DigestOptions` to use.
+///
+/// # Returns
+///
+/// A `String` containing the Markdown document.
+pub fn render_markdown(entries: &[Entry], options: &DigestOptions) -> String {
+    let mut out = format!("# {}\n", escape_markdown(&options.heading));
+    for group in group_by_title(entries) {
+        let title = display_title(group.title);
+        out.push_str(&format!("\n## [{}]({})\n", escape_markdown(title), title_url(group.title)));
+        let paged = group.pages(options).len() > 1;
+        for (page_number, page) in group.pages(options).enumerate() {
+            if paged {
+                out.push_str(&format!("\n### Page {}\n", page_number + 1));
+            }
+            for entry in page.iter() {
+                out.push_str(&format!("\n{}\n\n", content_to_markdown(&entry.content)));
+                let date = if entry.id == 0 {
+                    escape_markdown(&entry.date)
+                } else {
+                    format!("[{}]({})", escape_markdown(&entry.date), entry_url(entry.id))
+                };
+                let author = if entry.username.is_empty() { String::new() } else { format!("*{}*", escape_markdown(&entry.username)) };
+                let parts: Vec<String> = [author, date].into_iter().filter(|p| !p.is_empty()).collect();
+                out.push_str(&format!("— {}\n\n---\n", parts.join(" · ")));
+            }
+        }
+    }
+    out
+}
+
+/// Renders entries as a self-contained HTML page with inline styles.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` to render.
+/// * `options` - The `DigestOptions` to use.
+///
+/// # Returns
+///
+/// A `String` containing the HTML document.
+pub fn render_html(entries: &[Entry], options: &DigestOptions) -> String {
+    let mut body = String::new();
+    for group in group_by_title(entries) {
+        let title = display_title(group.title);
+        body.push_str(&format!(
+            "<section>\n<h2><a href=\"{}\">{}</a></h2>\n",
+            escape_html(&title_url(group.title)),
+            escape_html(title)
+        ));
+        let paged = group.pages(options).len() > 1;
+        for (page_number, page) in group.pages(options).enumerate() {
+            if paged {
+                body.push_str(&format!("<h3>Page {}</h3>\n", page_number + 1));
+            }
+            body.push_str("<ol class=\"entries\">\n");
+            for entry in page.iter() {
+                body.push_str(&format!(
+                    "<li><div class=\"content\">{}</div>{}</li>\n",
+                    content_to_html(&entry.content),
+                    entry_footer_html(entry)
+                ));
+            }
+            body.push_str("</ol>\n");
+        }
+        body.push_str("</section>\n");
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"tr\">\n<head>\n<meta charset=\"utf-8\">\n<title>{heading}</title>\n<style>\n{style}</style>\n</head>\n<body>\n<h1>{heading}</h1>\n{body}</body>\n</html>\n",
+        heading = escape_html(&options.heading),
+        style = STYLE,
+        body = body
+    )
+}
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 48em; margin: 2em auto; padding: 0 1em; line-height: 1.5; color: #222; }
+a { color: #53a245; text-decoration: none; }
+ol.entries { padding-left: 1.5em; }
+ol.entries li { margin-bottom: 1.5em; }
+footer { color: #888; font-size: 0.85em; text-align: right; }
+";
+
+/// Exports entries to a Markdown file.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the Markdown file to be created.
+/// * `options` - The `DigestOptions` to use.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO error occurred during export.
+pub fn export_to_markdown(entries: Vec<Entry>, file_name: &str, options: &DigestOptions) -> Result<(), Error> {
+    let mut file = File::create(file_name)?;
+    file.write_all(render_markdown(&entries, options).as_bytes())
+}
+
+/// Exports entries to a self-contained HTML file.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the HTML file to be created.
+/// * `options` - The `DigestOptions` to use.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO error occurred during export.
+pub fn export_to_html(entries: Vec<Entry>, file_name: &str, options: &DigestOptions) -> Result<(), Error> {
+    let mut file = File::create(file_name)?;
+    file.write_all(render_html(&entries, options).as_bytes())
+}
+
+/// Exports entries to an EPUB 3 book with one chapter per title.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the EPUB file to be created.
+/// * `options` - The `DigestOptions` to use; `heading` becomes the book title.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO error occurred during export.
+#[cfg(feature = "epub")]
+pub fn export_to_epub(entries: Vec<Entry>, file_name: &str, options: &DigestOptions) -> Result<(), Error> {
+    let file = File::create(file_name)?;
+    write_epub(&entries, file, options)
+}
+
+/// Writes entries as an EPUB 3 book to any seekable writer.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` to be written.
+/// * `writer` - The destination implementing `Write + Seek`.
+/// * `options` - The `DigestOptions` to use; `heading` becomes the book title.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the book was written.
+/// * `Err(Error)` - If an IO or archive error occurred.
+#[cfg(feature = "epub")]
+pub fn write_epub<W: Write + std::io::Seek>(entries: &[Entry], writer: W, options: &DigestOptions) -> Result<(), Error> {
+    use crate::util::fnv1a;
+    use zip::write::FileOptions;
+    use zip::CompressionMethod;
+
+    let chapters = group_by_title(entries);
+    let heading = escape_html(&options.heading);
+
+    // a fixed hash keeps the identifier of the same entries stable across builds and Rust releases
+    let hash = fnv1a(entries.iter().flat_map(|entry| {
+        [entry.title.as_bytes(), entry.date.as_bytes(), entry.content.as_bytes()]
+            .into_iter()
+            .flat_map(|field| field.iter().copied().chain([0]))
+    }));
+    let identifier = format!("urn:rustysozluk:{hash:016x}");
+    let modified = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+
+    let mut zip = zip::ZipWriter::new(writer);
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file("mimetype", stored).map_err(Error::other)?;
+    zip.write_all(b"application/epub+zip")?;
+    zip.start_file("META-INF/container.xml", deflated).map_err(Error::other)?;
+    zip.write_all(EPUB_CONTAINER.as_bytes())?;
+
+    let mut manifest = String::new();
+    let mut spine = String::new();
+    let mut nav = String::new();
+    for (index, chapter) in chapters.iter().enumerate() {
+        let name = format!("chapter{}.xhtml", index + 1);
+        let title = escape_html(display_title(chapter.title));
+        let mut body = format!("<h1><a href=\"{}\">{}</a></h1>\n", escape_html(&title_url(chapter.title)), title);
+        let paged = chapter.pages(options).len() > 1;
+        for (page_number, page) in chapter.pages(options).enumerate() {
+            if paged {
+                body.push_str(&format!("<h2>Page {}</h2>\n", page_number + 1));
+            }
+            for entry in page.iter() {
+                body.push_str(&format!(
+                    "<div class=\"entry\"><p>{}</p>{}</div>\n<hr/>\n",
+                    content_to_html(&entry.content),
+                    entry_footer_html(entry).replace("&middot;", "&#183;")
+                ));
+            }
+        }
+        zip.start_file(format!("OEBPS/{}", name), deflated).map_err(Error::other)?;
+        zip.write_all(xhtml_document(&title, &body).as_bytes())?;
+        manifest.push_str(&format!("<item id=\"c{0}\" href=\"{1}\" media-type=\"application/xhtml+xml\"/>\n", index + 1, name));
+        spine.push_str(&format!("<itemref idref=\"c{}\"/>\n", index + 1));
+        nav.push_str(&format!("<li><a href=\"{}\">{}</a></li>\n", name, title));
+    }
+
+    zip.start_file("OEBPS/nav.xhtml", deflated).map_err(Error::other)?;
+    let nav_body = format!("<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n<ol>\n{}</ol>\n</nav>\n", heading, nav);
+    zip.write_all(xhtml_document(&heading, &nav_body).as_bytes())?;
+
+    zip.start_file("OEBPS/content.opf", deflated).map_err(Error::other)?;
+    let package = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\" xml:lang=\"tr\">
+<metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">
+<dc:identifier id=\"book-id\">{identifier}</dc:identifier>
+<dc:title>{heading}</dc:title>
+<dc:language>tr</dc:language>
+<meta property=\"dcterms:modified\">{modified}</meta>
+</metadata>
+<manifest>
+<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>
+{manifest}</manifest>
+<spine>
+{spine}</spine>
+</package>
+"
+    );
+    zip.write_all(package.as_bytes())?;
+    zip.finish().map_err(Error::other)?;
+    Ok(())
+}
+
+#[cfg(feature = "epub")]
+const EPUB_CONTAINER: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">
+<rootfiles>
+<rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\"/>
+</rootfiles>
+</container>
+";
+
+#[cfg(feature = "epub")]
+fn xhtml_document(title: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<!DOCTYPE html>
+<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"tr\" xml:lang=\"tr\">
+<head><meta charset=\"utf-8\"/><title>{title}</title></head>
+<body>
+{body}</body>
+</html>
+"
+    )
+}
@@ -0,0 +1,448 @@
+//! # RustySozluk Exporter
+//!
+//! `rustysozluk_exporter` is a module responsible for exporting scraped data into different formats
+//! and loading previously exported data back.
+//! Currently, it supports CSV, JSON and NDJSON (JSON Lines) formats, plus Arrow and Parquet
+//! through the `arrow` and `parquet` cargo features. Readable Markdown, HTML and EPUB digests
+//! are rendered by the `digest` submodule.
+
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use crate::parser::Entry;
+
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod digest;
+
+/// A column that can be written by the CSV exporter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+    Content,
+    Date,
+    Username,
+    Id,
+    Title,
+    Favorites,
+}
+
+impl CsvColumn {
+    /// Every column, in the order used by `CsvOptions::all_columns`.
+    pub const ALL: [CsvColumn; 6] = [
+        CsvColumn::Id,
+        CsvColumn::Title,
+        CsvColumn::Username,
+        CsvColumn::Date,
+        CsvColumn::Favorites,
+        CsvColumn::Content,
+    ];
+
+    /// Returns the header written for the column.
+    pub fn header(&self) -> &'static str {
+        match self {
+            CsvColumn::Content => "Content",
+            CsvColumn::Date => "Date",
+            CsvColumn::Username => "Username",
+            CsvColumn::Id => "Id",
+            CsvColumn::Title => "Title",
+            CsvColumn::Favorites => "Favorites",
+        }
+    }
+
+    fn value(&self, entry: &Entry) -> String {
+        match self {
+            CsvColumn::Content => entry.content.clone(),
+            CsvColumn::Date => entry.date.clone(),
+            CsvColumn::Username => entry.username.clone(),
+            CsvColumn::Id => entry.id.to_string(),
+            CsvColumn::Title => entry.title.clone(),
+            CsvColumn::Favorites => entry.favorites.to_string(),
+        }
+    }
+}
+
+/// How fields are quoted in CSV output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvQuoting {
+    /// Quote only fields that contain the delimiter, quotes or line breaks.
+    Necessary,
+    /// Quote every field.
+    Always,
+    /// Quote every field that is not a number.
+    NonNumeric,
+    /// Never quote fields, even if the output becomes ambiguous.
+    Never,
+}
+
+impl From<CsvQuoting> for csv::QuoteStyle {
+    fn from(quoting: CsvQuoting) -> Self {
+        match quoting {
+            CsvQuoting::Necessary => csv::QuoteStyle::Necessary,
+            CsvQuoting::Always => csv::QuoteStyle::Always,
+            CsvQuoting::NonNumeric => csv::QuoteStyle::NonNumeric,
+            CsvQuoting::Never => csv::QuoteStyle::Never,
+        }
+    }
+}
+
+/// Options controlling the CSV exporter.
+///
+/// The default matches `export_to_csv`: "Content", "Date" and "Username" columns separated by commas.
+///
+/// # Example
+///
+/// ```no_run
+/// use rustysozluk::{export_to_csv_with, CsvColumn, CsvOptions};
+///
+/// let options = CsvOptions {
+///     columns: vec![CsvColumn::Id, CsvColumn::Username, CsvColumn::Content],
+///     ..CsvOptions::excel()
+/// };
+/// export_to_csv_with(Vec::new(), "entries.csv", &options).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Columns to write, in order.
+    pub columns: Vec<CsvColumn>,
+    /// Field delimiter, e.g. `b';'` for Excel with a Turkish locale.
+    pub delimiter: u8,
+    /// Quoting style of the fields.
+    pub quoting: CsvQuoting,
+    /// Write a UTF-8 byte order mark so that Excel detects the encoding and shows Turkish characters correctly.
+    pub bom: bool,
+    /// Replace line breaks in the content with a single space.
+    pub flatten_newlines: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            columns: vec![CsvColumn::Content, CsvColumn::Date, CsvColumn::Username],
+            delimiter: b',',
+            quoting: CsvQuoting::Necessary,
+            bom: false,
+            flatten_newlines: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Options for files opened in Excel with a Turkish locale: `;` delimiter, a UTF-8 BOM and single-line content.
+    pub fn excel() -> Self {
+        CsvOptions {
+            delimiter: b';',
+            bom: true,
+            flatten_newlines: true,
+            ..CsvOptions::default()
+        }
+    }
+
+    /// Returns the options with every `Entry` field selected as a column.
+    pub fn all_columns(self) -> Self {
+        CsvOptions { columns: CsvColumn::ALL.to_vec(), ..self }
+    }
+}
+
+/// Exports a list of entries to a CSV file.
+///
+/// The CSV file will have columns for "Content", "Date", and "Username".
+/// Use `export_to_csv_with` to choose the columns and the CSV dialect.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the CSV file to be created.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO error occurred during export.
+pub fn export_to_csv(entries: Vec<Entry>, file_name: &str) -> Result<(), Error> {
+    export_to_csv_with(entries, file_name, &CsvOptions::default())
+}
+
+/// Exports a list of entries to a CSV file using the given options.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the CSV file to be created.
+/// * `options` - The `CsvOptions` to use.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO error occurred during export.
+pub fn export_to_csv_with(entries: Vec<Entry>, file_name: &str, options: &CsvOptions) -> Result<(), Error> {
+    let file = File::create(file_name)?;
+    write_csv(&entries, BufWriter::new(file), options)
+}
+
+/// Writes entries as CSV to any writer using the given options.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` to be written.
+/// * `writer` - The destination implementing `Write`.
+/// * `options` - The `CsvOptions` to use.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If all entries were written.
+/// * `Err(Error)` - If an IO error occurred.
+pub fn write_csv<W: Write>(entries: &[Entry], mut writer: W, options: &CsvOptions) -> Result<(), Error> {
+    if options.columns.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "at least one CSV column must be selected"));
+    }
+    if options.bom {
+        writer.write_all("\u{feff}".as_bytes())?;
+    }
+    let mut wtr = WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .quote_style(options.quoting.into())
+        .from_writer(writer);
+    wtr.write_record(options.columns.iter().map(CsvColumn::header))?;
+    for entry in entries {
+        wtr.write_record(options.columns.iter().map(|column| {
+            let value = column.value(entry);
+            if options.flatten_newlines {
+                value.split(['\r', '\n']).filter(|line| !line.trim().is_empty()).map(str::trim).collect::<Vec<_>>().join(" ")
+            } else {
+                value
+            }
+        }))?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Exports a list of entries to a JSON file.
+///
+/// The JSON file will contain an array of entry objects, each with "content", "date", and "username" fields.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the JSON file to be created.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO error occurred during export.
+///
+/// The example is similar with export_to_csv. See the "examples" folder for the full example and usage.
+pub fn export_to_json(entries: Vec<Entry>, file_name: &str) -> Result<(), Error> {
+    let json_string = serde_json::to_string(&entries)?;
+    let mut file = File::create(file_name)?;
+    file.write_all(json_string.as_bytes())?;
+    Ok(())
+}
+
+/// Exports a list of entries to an NDJSON (JSON Lines) file.
+///
+/// Each line of the file holds a single entry object, which makes the output easy to stream
+/// into tools such as `jq`, DuckDB or Spark.
+///
+/// # Arguments
+///
+/// * `entries` - A `Vec<Entry>` containing the entries to be exported.
+/// * `file_name` - A `&str` specifying the name of the NDJSON file to be created.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If the export was successful.
+/// * `Err(Error)` - If an IO error occurred during export.
+pub fn export_to_ndjson(entries: Vec<Entry>, file_name: &str) -> Result<(), Error> {
+    let file = File::create(file_name)?;
+    write_ndjson(&entries, BufWriter::new(file))
+}
+
+/// Writes entries as NDJSON (one JSON object per line) to any writer.
+///
+/// This is useful for appending to an existing file or writing to stdout.
+///
+/// # Arguments
+///
+/// * `entries` - A slice of `Entry` to be written.
+/// * `writer` - The destination implementing `Write`.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(())` - If all entries were written.
+/// * `Err(Error)` - If an IO or serialization error occurred.
+pub fn write_ndjson<W: Write>(entries: &[Entry], mut writer: W) -> Result<(), Error> {
+    for entry in entries {
+        serde_json::to_writer(&mut writer, entry)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Loads entries from a JSON file created by `export_to_json`.
+///
+/// # Arguments
+///
+/// * `file_name` - A `&str` specifying the path of the JSON file.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(Vec<Entry>)` - The entries stored in the file.
+/// * `Err(Error)` - If the file could not be read or is not a JSON array of entries.
+pub fn import_from_json(file_name: &str) -> Result<Vec<Entry>, Error> {
+    let file = File::open(file_name)?;
+    let entries = serde_json::from_reader(BufReader::new(file))?;
+    Ok(entries)
+}
+
+/// Loads entries from an NDJSON file created by `export_to_ndjson`.
+///
+/// Use `read_ndjson` instead if the file is too big to keep in memory.
+///
+/// # Arguments
+///
+/// * `file_name` - A `&str` specifying the path of the NDJSON file.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(Vec<Entry>)` - The entries stored in the file.
+/// * `Err(Error)` - If the file could not be read or a line is not a valid entry.
+pub fn import_from_ndjson(file_name: &str) -> Result<Vec<Entry>, Error> {
+    let file = File::open(file_name)?;
+    read_ndjson(BufReader::new(file)).collect()
+}
+
+/// Streams entries from an NDJSON source, one line at a time.
+///
+/// Blank lines are skipped.
+///
+/// # Arguments
+///
+/// * `reader` - Any buffered reader holding NDJSON data.
+///
+/// # Returns
+///
+/// An iterator yielding a `Result<Entry, Error>` for every non-empty line.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::fs::File;
+/// use std::io::BufReader;
+/// use rustysozluk::read_ndjson;
+///
+/// let file = File::open("entries.ndjson").unwrap();
+/// for entry in read_ndjson(BufReader::new(file)) {
+///     println!("{}", entry.unwrap().content);
+/// }
+/// ```
+pub fn read_ndjson<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Entry, Error>> {
+    reader.lines().filter_map(|line| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(serde_json::from_str(&line).map_err(Error::from)),
+        Err(e) => Some(Err(e)),
+    })
+}
+
+/// Loads entries from a CSV file created by `export_to_csv`.
+///
+/// Columns are matched by their header name, case-insensitively, so the column order does not matter.
+/// Columns that are missing from the file are left empty.
+///
+/// # Arguments
+///
+/// * `file_name` - A `&str` specifying the path of the CSV file.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(Vec<Entry>)` - The entries stored in the file.
+/// * `Err(Error)` - If the file could not be read or parsed.
+pub fn import_from_csv(file_name: &str) -> Result<Vec<Entry>, Error> {
+    let file = File::open(file_name)?;
+    read_csv(file)
+}
+
+/// Loads entries from any CSV source with a header row.
+///
+/// See `import_from_csv` for how columns are matched. The delimiter (`,`, `;` or tab) is detected
+/// from the header row, so files written with `CsvOptions::excel` can be loaded back as well.
+///
+/// # Arguments
+///
+/// * `reader` - The CSV source implementing `Read`.
+///
+/// # Returns
+///
+/// A `Result` which is either:
+/// * `Ok(Vec<Entry>)` - The parsed entries.
+/// * `Err(Error)` - If the data could not be read or parsed.
+pub fn read_csv<R: Read>(reader: R) -> Result<Vec<Entry>, Error> {
+    let mut reader = BufReader::new(reader);
+    let delimiter = detect_delimiter(reader.fill_buf()?);
+    let mut rdr = ReaderBuilder::new().delimiter(delimiter).from_reader(reader);
+    let headers = rdr.headers()?.clone();
+    if !headers.iter().any(|h| column_name(h).is_some()) {
+        return Err(Error::new(ErrorKind::InvalidData, "CSV header does not contain any entry columns"));
+    }
+    let mut entries = Vec::new();
+    for result in rdr.records() {
+        entries.push(entry_from_record(&headers, &result?)?);
+    }
+    Ok(entries)
+}
+
+/// Picks the most frequent candidate delimiter in the first line of the data.
+///
+/// Ties, including a line without any candidate, are broken in the order `,`, `;`, tab, so
+/// `,` wins unless another delimiter occurs more often.
+fn detect_delimiter(data: &[u8]) -> u8 {
+    let header = data.split(|&b| b == b'\n').next().unwrap_or_default();
+    let count = |delimiter: u8| header.iter().filter(|&&b| b == delimiter).count();
+    [b';', b'\t'].into_iter().fold(b',', |best, candidate| if count(candidate) > count(best) { candidate } else { best })
+}
+
+/// Normalizes a CSV header and returns it if it names an `Entry` field.
+fn column_name(header: &str) -> Option<String> {
+    let name = header.trim_start_matches('\u{feff}').trim().to_lowercase();
+    match name.as_str() {
+        "content" | "date" | "username" | "id" | "title" | "favorites" => Some(name),
+        _ => None,
+    }
+}
+
+/// Builds an entry from a CSV record using the header row to locate each field.
+fn entry_from_record(headers: &StringRecord, record: &StringRecord) -> Result<Entry, Error> {
+    let mut entry = Entry::default();
+    for (header, value) in headers.iter().zip(record.iter()) {
+        match column_name(header).as_deref() {
+            Some("content") => entry.content = value.to_string(),
+            Some("date") => entry.date = value.to_string(),
+            Some("username") => entry.username = value.to_string(),
+            Some("title") => entry.title = value.to_string(),
+            Some("id") => entry.id = parse_number(value)?,
+            Some("favorites") => entry.favorites = parse_number(value)?,
+            _ => {}
+        }
+    }
+    Ok(entry)
+}
+
+/// Parses a numeric CSV field, treating an empty field as zero.
+fn parse_number<T: std::str::FromStr + Default>(value: &str) -> Result<T, Error>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(T::default());
+    }
+    value.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
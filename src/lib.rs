@@ -1,10 +1,24 @@
 mod http_client;
 mod parser;
 mod exporter;
+mod util;
 pub mod analyzer;
+#[cfg(feature = "sqlite")]
+pub mod storage;
 pub use http_client::fetch_page;
-pub use parser::{fetch_user, fetch_title};
+pub use parser::{fetch_user, fetch_title, fetch_user_into, fetch_title_into};
 pub use exporter::export_to_csv;
+pub use exporter::{export_to_csv_with, write_csv, CsvColumn, CsvOptions, CsvQuoting};
 pub use exporter::export_to_json;
+pub use exporter::{export_to_ndjson, write_ndjson};
+pub use exporter::{import_from_csv, import_from_json, import_from_ndjson, read_csv, read_ndjson};
+#[cfg(feature = "arrow")]
+pub use exporter::columnar::{entries_to_record_batch, entry_schema, export_to_arrow};
+#[cfg(feature = "parquet")]
+pub use exporter::columnar::{export_to_parquet, write_parquet, ParquetOptions};
+pub use exporter::digest::{export_to_html, export_to_markdown, render_html, render_markdown, DigestOptions};
+#[cfg(feature = "epub")]
+pub use exporter::digest::{export_to_epub, write_epub};
+pub use parser::{bkz_references, BkzReference, Entry, EntryLink, EntrySink, RustySozlukError, parse_entry_date};
 pub use tokio;
 
@@ -5,12 +5,15 @@
 
 
 
-use scraper::{Html, Selector,ElementRef};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use scraper::{Html, Node, Selector,ElementRef};
 use crate::http_client;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Removes HTML tags and returns plain text content.
 ///
+/// Line breaks (`<br>`) are kept as `\n` so that multi-paragraph entries stay readable.
+///
 /// # Arguments
 ///
 /// * `element` - A reference to an `ElementRef`.
@@ -18,24 +21,229 @@ use serde::Serialize;
 /// # Returns
 ///
 /// A `String` containing the cleaned-up text.
-
 fn clean_html_content(element: &ElementRef) -> String {
-    element.text().collect::<Vec<_>>().concat().trim().to_string()
+    let mut text = String::new();
+    for node in element.descendants() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) if e.name() == "br" => text.push('\n'),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+/// A `(bkz: ...)` reference found in the content of an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BkzReference {
+    /// Byte range of the referenced text inside the content, without the `(bkz: ` prefix and `)` suffix.
+    pub range: std::ops::Range<usize>,
+    /// The referenced title, or `#<id>` when an entry is referenced.
+    pub target: String,
+}
+
+impl BkzReference {
+    /// Returns the referenced entry id if the reference points to an entry (`(bkz: #12345)`).
+    pub fn entry_id(&self) -> Option<u64> {
+        self.target.strip_prefix('#').and_then(|id| id.parse().ok())
+    }
+}
+
+/// Finds the `(bkz: ...)` references in the content of an entry.
+///
+/// # Arguments
+///
+/// * `content` - A `&str` holding the plain text of an entry.
+///
+/// # Returns
+///
+/// A `Vec<BkzReference>` in the order the references appear.
+pub fn bkz_references(content: &str) -> Vec<BkzReference> {
+    const PREFIX: &str = "(bkz:";
+    let mut references = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = content[offset..].find(PREFIX) {
+        let start = offset + found + PREFIX.len();
+        let Some(length) = content[start..].find(')') else { break };
+        let raw = &content[start..start + length];
+        let leading = raw.len() - raw.trim_start().len();
+        let target = raw.trim();
+        if !target.is_empty() && !target.contains('\n') {
+            let begin = start + leading;
+            references.push(BkzReference { range: begin..begin + target.len(), target: target.to_string() });
+        }
+        offset = start + length + 1;
+    }
+    references
+}
+
+/// A link inside the content of an entry.
+///
+/// The plain-text `content` keeps only the visible text of links, so their targets are captured
+/// separately while the HTML is parsed.
+///
+/// # Example
+///
+/// ```
+/// use rustysozluk::EntryLink;
+///
+/// let bkz = EntryLink { href: "/?q=rust+programlama+dili".to_string(), text: "rust programlama dili".to_string() };
+/// assert_eq!(bkz.title().as_deref(), Some("rust programlama dili"));
+/// let entry = EntryLink { href: "https://eksisozluk.com/entry/12345".to_string(), text: "#12345".to_string() };
+/// assert_eq!(entry.entry_id(), Some(12345));
+/// assert_eq!(entry.title(), None);
+/// // `(bkz: #12345)` is rendered as a search for `#12345`
+/// let search = EntryLink { href: "/?q=%2312345".to_string(), text: "#12345".to_string() };
+/// assert_eq!(search.entry_id(), Some(12345));
+/// assert_eq!(search.title(), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryLink {
+    /// The `href` of the link as found in the page, e.g. `/entry/12345` or `/?q=rust`.
+    pub href: String,
+    /// The visible text of the link.
+    pub text: String,
+}
+
+impl EntryLink {
+    /// Returns the referenced entry id if the link points to an entry (`/entry/12345`) or
+    /// searches for one (`/?q=%2312345`, as rendered for `(bkz: #12345)`).
+    pub fn entry_id(&self) -> Option<u64> {
+        if let Some(id) = site_path(&self.href)?.strip_prefix("/entry/") {
+            let end = id.find(|c: char| !c.is_ascii_digit()).unwrap_or(id.len());
+            return id[..end].parse().ok();
+        }
+        let query = self.query()?;
+        let id = query.strip_prefix('#')?;
+        if id.bytes().all(|b| b.is_ascii_digit()) { id.parse().ok() } else { None }
+    }
+
+    /// Returns the referenced title if the link is a title search (`/?q=rust+programlama`),
+    /// as used by `(bkz: ...)` references and backtick mentions.
+    pub fn title(&self) -> Option<String> {
+        let title = self.query()?;
+        (!title.is_empty() && !title.starts_with('#')).then_some(title)
+    }
+
+    /// Returns the decoded and trimmed `q` value of a search link.
+    fn query(&self) -> Option<String> {
+        let query = site_path(&self.href)?.strip_prefix("/?")?;
+        let value = query.split('&').find_map(|pair| pair.strip_prefix("q="))?;
+        Some(percent_decode(value).trim().to_string())
+    }
+}
+
+/// Returns the path of a link to eksisozluk.com, or `None` for links to other sites.
+fn site_path(href: &str) -> Option<&str> {
+    let path = ["https://eksisozluk.com", "http://eksisozluk.com", "//eksisozluk.com"]
+        .iter()
+        .find_map(|host| href.strip_prefix(host))
+        .unwrap_or(href);
+    path.starts_with('/').then_some(path)
+}
+
+/// Decodes a URL query value (`%C3%BC` and `+` for spaces).
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' => match value.get(index + 1..index + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(byte) => {
+                    decoded.push(byte);
+                    index += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
 }
 
+/// Collects the links inside an element, in document order.
+fn content_links(element: &ElementRef) -> Vec<EntryLink> {
+    let link_selector = Selector::parse("a[href]").unwrap();
+    element
+        .select(&link_selector)
+        .filter_map(|link| {
+            let href = link.value().attr("href")?.trim();
+            (!href.is_empty()).then(|| EntryLink { href: href.to_string(), text: clean_html_content(&link) })
+        })
+        .collect()
+}
 
 /// Struct to hold an individual entry.
 ///
-/// It contains the content, date, and username associated with an entry.
-#[derive(Debug, Serialize)]
+/// It contains the content, date, and username associated with an entry, together with
+/// the entry id, the title it was written under and its favorite count when the page provides them.
+/// Entries can be serialized for export and deserialized again when loading a saved dataset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Entry {
     pub content: String,
     pub date: String,
     pub username: String,
+    /// Eksi Sozluk entry id, `0` if unknown.
+    #[serde(default)]
+    pub id: u64,
+    /// Title (başlık) the entry belongs to, empty if unknown.
+    #[serde(default)]
+    pub title: String,
+    /// Number of times the entry was favorited.
+    #[serde(default)]
+    pub favorites: u32,
+    /// Links inside the content with their targets; empty when unknown, e.g. for entries loaded from CSV or SQLite.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<EntryLink>,
+}
+
+impl Entry {
+    /// Returns the time the entry was written, parsed from `date`.
+    ///
+    /// Dates are in the site's local time (Europe/Istanbul), e.g. `"15.09.2023 21:35"`.
+    pub fn created_at(&self) -> Option<NaiveDateTime> {
+        parse_entry_date(&self.date).map(|(created, _)| created)
+    }
+
+    /// Returns the time the entry was last edited, if it was edited.
+    ///
+    /// Edited entries have dates like `"15.09.2023 21:35 ~ 21:40"` or `"15.09.2023 21:35 ~ 16.09.2023 10:00"`.
+    pub fn edited_at(&self) -> Option<NaiveDateTime> {
+        parse_entry_date(&self.date).and_then(|(_, edited)| edited)
+    }
+}
+
+/// Parses an entry date string into its creation time and optional edit time.
+///
+/// # Arguments
+///
+/// * `date` - A `&str` such as `"15.09.2023 21:35 ~ 16.09.2023 10:00"`.
+///
+/// # Returns
+///
+/// `Some((created, edited))` if the creation time could be parsed, `None` otherwise.
+pub fn parse_entry_date(date: &str) -> Option<(NaiveDateTime, Option<NaiveDateTime>)> {
+    const FORMAT: &str = "%d.%m.%Y %H:%M";
+    let mut parts = date.split('~').map(str::trim);
+    let created_text = parts.next()?;
+    let created = NaiveDateTime::parse_from_str(created_text, FORMAT)
+        .or_else(|_| NaiveDate::parse_from_str(created_text, "%d.%m.%Y").map(|d| d.and_time(NaiveTime::MIN)))
+        .ok()?;
+    let edited = parts.next().and_then(|edit| {
+        NaiveDateTime::parse_from_str(edit, FORMAT).ok().or_else(|| {
+            NaiveTime::parse_from_str(edit, "%H:%M").ok().map(|time| created.date().and_time(time))
+        })
+    });
+    Some((created, edited))
 }
 
 /// Extracts entries from the HTML content.
 ///
+/// Both title pages and user pages wrap entries under an `h1#title` heading, which is used to fill `Entry::title`.
+///
 /// # Arguments
 ///
 /// * `html` - A `str` slice that holds the HTML document.
@@ -44,24 +252,36 @@ pub struct Entry {
 /// # Returns
 ///
 /// A `Vec<Entry>` containing the extracted entries.
-
 fn extract_entries(html: &str, limit: usize) -> Vec<Entry> {
     let document = Html::parse_document(html);
-    let entry_selector = Selector::parse("li[data-id]").unwrap();
+    let item_selector = Selector::parse("h1#title, li[data-id]").unwrap();
     let content_selector = Selector::parse("div.content").unwrap();
     let date_selector = Selector::parse("a.entry-date").unwrap();
     let username_selector = Selector::parse("div#entry-author a.entry-author").unwrap(); 
 
     let mut entries = Vec::new();
+    let mut title = String::new();
 
-    for entry in document.select(&entry_selector).take(limit) {
-        if let Some(content_element) = entry.select(&content_selector).next() {
-            if let Some(date_element) = entry.select(&date_selector).next() {
-                if let Some(username_element) = entry.select(&username_selector).next() {
+    for item in document.select(&item_selector) {
+        if entries.len() >= limit {
+            break;
+        }
+        if item.value().name() == "h1" {
+            title = item.value().attr("data-title")
+                .map(str::to_string)
+                .unwrap_or_else(|| clean_html_content(&item));
+            continue;
+        }
+        if let Some(content_element) = item.select(&content_selector).next() {
+            if let Some(date_element) = item.select(&date_selector).next() {
+                if let Some(username_element) = item.select(&username_selector).next() {
                     let content = clean_html_content(&content_element);
+                    let links = content_links(&content_element);
                     let date = clean_html_content(&date_element);
                     let username = clean_html_content(&username_element);
-                    entries.push(Entry { content, date, username });
+                    let id = numeric_attr(&item, "data-id");
+                    let favorites = numeric_attr(&item, "data-favorite-count");
+                    entries.push(Entry { content, date, username, id, title: title.clone(), favorites, links });
                 }
             }
         }
@@ -70,6 +290,33 @@ fn extract_entries(html: &str, limit: usize) -> Vec<Entry> {
     entries
 }
 
+/// Reads a numeric attribute of an element, returning zero if it is missing or malformed.
+fn numeric_attr<T: std::str::FromStr + Default>(element: &ElementRef, name: &str) -> T {
+    element.value().attr(name).and_then(|value| value.trim().parse().ok()).unwrap_or_default()
+}
+
+/// A destination for entries as they are fetched, page by page.
+///
+/// Implement this trait to stream entries into a database or file while a large title is being
+/// scraped, instead of collecting everything in memory first. `Vec<Entry>` implements it by
+/// appending the entries.
+pub trait EntrySink {
+    /// The error returned when the sink cannot store a batch.
+    type Error: std::fmt::Display;
+
+    /// Stores one batch of freshly fetched entries.
+    fn consume(&mut self, entries: &[Entry]) -> Result<(), Self::Error>;
+}
+
+impl EntrySink for Vec<Entry> {
+    type Error = std::convert::Infallible;
+
+    fn consume(&mut self, entries: &[Entry]) -> Result<(), Self::Error> {
+        self.extend_from_slice(entries);
+        Ok(())
+    }
+}
+
 /// Fetches and aggregates entries for a given title up to a limit.
 ///
 /// # Arguments
@@ -82,26 +329,45 @@ fn extract_entries(html: &str, limit: usize) -> Vec<Entry> {
 /// A `Result` which is:
 /// * `Ok(Vec<Entry>)` - A vector containing the fetched entries.
 /// * `Err(RustySozlukError)` - An error of type `RustySozlukError`.
-
 pub async fn fetch_title(base_url: &str, limit: usize) -> Result<Vec<Entry>, RustySozlukError> {
+    let mut all_entries = Vec::new();
+    fetch_title_into(base_url, limit, &mut all_entries).await?;
+    Ok(all_entries)
+}
+
+/// Fetches entries for a given title up to a limit and hands each page to a sink.
+///
+/// # Arguments
+///
+/// * `base_url` - A `&str` that defines the base URL of the page.
+/// * `limit` - A `usize` that defines the maximum number of entries to fetch.
+/// * `sink` - The `EntrySink` receiving the entries of every fetched page.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// * `Ok(usize)` - The number of entries passed to the sink.
+/// * `Err(RustySozlukError)` - An error of type `RustySozlukError`.
+pub async fn fetch_title_into<S: EntrySink>(base_url: &str, limit: usize, sink: &mut S) -> Result<usize, RustySozlukError> {
     if limit == 0 {
         return Err(RustySozlukError::Other("Limit cannot be zero".to_string()));
     }
-    let mut all_entries = Vec::new();
+    let mut fetched = 0;
     let mut current_page = 1;
-    while all_entries.len() < limit {
+    while fetched < limit {
         let page_url = format!("{}?p={}", base_url, current_page);
         let page_html = http_client::fetch_page(&page_url)
         .await
         .map_err(|_| RustySozlukError::NetworkError)?;
-        let mut entries = extract_entries(&page_html, limit - all_entries.len());
+        let entries = extract_entries(&page_html, limit - fetched);
         if entries.is_empty() {
             break;
         }
-        all_entries.append(&mut entries);
+        sink.consume(&entries).map_err(|e| RustySozlukError::Sink(e.to_string()))?;
+        fetched += entries.len();
         current_page += 1;
     }
-    Ok(all_entries)
+    Ok(fetched)
 }
 
 /// Fetches a user's entries up to a given limit.
@@ -116,14 +382,32 @@ pub async fn fetch_title(base_url: &str, limit: usize) -> Result<Vec<Entry>, Rus
 /// A `Result` which is:
 /// * `Ok(Vec<Entry>)` - A vector containing the fetched entries.
 /// * `Err(RustySozlukError)` - An error of type `RustySozlukError`.
-
 pub async fn fetch_user(username: &str, limit: usize) -> Result<Vec<Entry>, RustySozlukError> {
+    let mut all_entries = Vec::new();
+    fetch_user_into(username, limit, &mut all_entries).await?;
+    Ok(all_entries)
+}
+
+/// Fetches a user's entries up to a given limit and hands each page to a sink.
+///
+/// # Arguments
+///
+/// * `username` - A `&str` that defines the username of the target user.
+/// * `limit` - A `usize` that defines the maximum number of entries to fetch.
+/// * `sink` - The `EntrySink` receiving the entries of every fetched page.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// * `Ok(usize)` - The number of entries passed to the sink.
+/// * `Err(RustySozlukError)` - An error of type `RustySozlukError`.
+pub async fn fetch_user_into<S: EntrySink>(username: &str, limit: usize, sink: &mut S) -> Result<usize, RustySozlukError> {
     if limit == 0 {
         return Err(RustySozlukError::Other("Limit cannot be zero".to_string()));
     }
-    let mut all_entries = Vec::new();
+    let mut fetched = 0;
     let mut current_page = 1;
-    while all_entries.len() < limit {
+    while fetched < limit {
         let user_url = format!("https://eksisozluk.com/son-entryleri?nick={}&p={}", username, current_page);
         let client = reqwest::Client::new();
         let response = client.get(&user_url)
@@ -133,24 +417,27 @@ pub async fn fetch_user(username: &str, limit: usize) -> Result<Vec<Entry>, Rust
             .map_err(|_| RustySozlukError::NetworkError)?;
         let body = response.text().await
             .map_err(|_| RustySozlukError::NetworkError)?;
-        let mut entries = extract_entries(&body, limit - all_entries.len());
+        let entries = extract_entries(&body, limit - fetched);
         if entries.is_empty() {
             break;
         }
-        all_entries.append(&mut entries);
+        sink.consume(&entries).map_err(|e| RustySozlukError::Sink(e.to_string()))?;
+        fetched += entries.len();
         current_page += 1;
     }
 
-    Ok(all_entries)
+    Ok(fetched)
 }
 
 /// Custom errors for RustySozluk.
 ///
-/// It contains errors related to network issues or other unspecified errors but this can be extended in the future.
+/// It contains errors related to network issues, entry sinks that failed to store a page, or other unspecified errors.
 #[derive(Debug, thiserror::Error)]
 pub enum RustySozlukError {
     #[error("Network error occurred")]
     NetworkError,
+    #[error("Sink error: {0}")]
+    Sink(String),
     #[error("Other error: {0}")]
     Other(String),
 }
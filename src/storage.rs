@@ -0,0 +1,308 @@
+//! # RustySozluk Storage
+//!
+//! `rustysozluk_storage` is a module providing a SQLite storage backend for scraped entries.
+//! It is only available with the `sqlite` cargo feature.
+//!
+//! Entries are keyed by their Eksi Sozluk entry id, so scraping the same title again updates
+//! the stored rows instead of duplicating them. When the content of an entry changes between
+//! scrapes, the previous version is kept in the `entry_revisions` table.
+
+use chrono::{NaiveDateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use std::path::Path;
+use crate::parser::{parse_entry_date, Entry, EntrySink};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS titles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    first_scraped TEXT NOT NULL,
+    last_scraped TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL UNIQUE,
+    first_scraped TEXT NOT NULL,
+    last_scraped TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS entries (
+    id INTEGER PRIMARY KEY,
+    title_id INTEGER REFERENCES titles(id),
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    content TEXT NOT NULL,
+    date TEXT NOT NULL,
+    created_at TEXT,
+    edited_at TEXT,
+    favorites INTEGER NOT NULL DEFAULT 0,
+    first_scraped TEXT NOT NULL,
+    last_scraped TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS entry_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry_id INTEGER NOT NULL REFERENCES entries(id),
+    content TEXT NOT NULL,
+    date TEXT NOT NULL,
+    replaced_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS entries_title ON entries(title_id);
+CREATE INDEX IF NOT EXISTS entries_user_created ON entries(user_id, created_at);
+CREATE INDEX IF NOT EXISTS revisions_entry ON entry_revisions(entry_id);
+";
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+const SELECT_ENTRY: &str = "
+SELECT e.content, e.date, u.username, e.id, COALESCE(t.name, ''), e.favorites
+FROM entries e
+JOIN users u ON u.id = e.user_id
+LEFT JOIN titles t ON t.id = e.title_id";
+
+/// Counts of what happened to each entry passed to `SqliteStore::save`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveSummary {
+    /// Entries that were not stored before.
+    pub inserted: usize,
+    /// Entries whose content or date changed since the last scrape.
+    pub updated: usize,
+    /// Entries that were already stored with the same content.
+    pub unchanged: usize,
+    /// Entries without an id, which cannot be keyed and were not stored.
+    pub skipped: usize,
+}
+
+/// A previous version of an entry, recorded when a re-scrape found different content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryRevision {
+    pub entry_id: u64,
+    pub content: String,
+    pub date: String,
+    /// When the newer version replaced this one, in UTC.
+    pub replaced_at: NaiveDateTime,
+}
+
+/// SQLite-backed store of titles, users and entries.
+///
+/// # Example
+///
+/// ```no_run
+/// use rustysozluk::{fetch_title_into, tokio};
+/// use rustysozluk::storage::SqliteStore;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut store = SqliteStore::open("sozluk.db")?;
+///     fetch_title_into("https://eksisozluk.com/rust-programlama-dili--5575227", 20, &mut store).await?;
+///     let entries = store.entries_for_title("rust programlama dili")?;
+///     println!("{} entries stored", entries.len());
+///     Ok(())
+/// }
+/// ```
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens (or creates) a database file and makes sure the schema exists.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Opens a database that lives only in memory, mainly useful for experiments.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Wraps an existing connection and makes sure the schema exists.
+    pub fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(SqliteStore { conn })
+    }
+
+    /// Gives access to the underlying connection for custom queries.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Inserts new entries and updates the ones that were scraped before.
+    ///
+    /// All entries are written in a single transaction, stamped with the current time as scrape time.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `Entry` to store. Entries with an `id` of `0` are skipped.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is either:
+    /// * `Ok(SaveSummary)` - How many entries were inserted, updated, unchanged or skipped.
+    /// * `Err(rusqlite::Error)` - If a database error occurred; nothing is written in that case.
+    pub fn save(&mut self, entries: &[Entry]) -> Result<SaveSummary> {
+        let now = Utc::now().naive_utc().format(TIMESTAMP_FORMAT).to_string();
+        let mut summary = SaveSummary::default();
+        let tx = self.conn.transaction()?;
+        for entry in entries {
+            if entry.id == 0 {
+                summary.skipped += 1;
+                continue;
+            }
+            let user_id = upsert_named(&tx, "users", "username", &entry.username, &now)?;
+            let title_id = if entry.title.is_empty() {
+                None
+            } else {
+                Some(upsert_named(&tx, "titles", "name", &entry.title, &now)?)
+            };
+            let (created_at, edited_at) = match parse_entry_date(&entry.date) {
+                Some((created, edited)) => (Some(format_timestamp(created)), edited.map(format_timestamp)),
+                None => (None, None),
+            };
+            let previous: Option<(String, String)> = tx
+                .query_row(
+                    "SELECT content, date FROM entries WHERE id = ?1",
+                    params![entry.id as i64],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            match previous {
+                None => {
+                    tx.execute(
+                        "INSERT INTO entries (id, title_id, user_id, content, date, created_at, edited_at, favorites, first_scraped, last_scraped)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)",
+                        params![entry.id as i64, title_id, user_id, entry.content, entry.date, created_at, edited_at, entry.favorites, now],
+                    )?;
+                    summary.inserted += 1;
+                }
+                Some((content, date)) => {
+                    if content != entry.content || date != entry.date {
+                        tx.execute(
+                            "INSERT INTO entry_revisions (entry_id, content, date, replaced_at) VALUES (?1, ?2, ?3, ?4)",
+                            params![entry.id as i64, content, date, now],
+                        )?;
+                        summary.updated += 1;
+                    } else {
+                        summary.unchanged += 1;
+                    }
+                    tx.execute(
+                        "UPDATE entries SET title_id = COALESCE(?2, title_id), user_id = ?3, content = ?4, date = ?5,
+                         created_at = ?6, edited_at = ?7, favorites = ?8, last_scraped = ?9 WHERE id = ?1",
+                        params![entry.id as i64, title_id, user_id, entry.content, entry.date, created_at, edited_at, entry.favorites, now],
+                    )?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    /// Returns a stored entry by its id.
+    pub fn entry(&self, id: u64) -> Result<Option<Entry>> {
+        let sql = format!("{} WHERE e.id = ?1", SELECT_ENTRY);
+        self.conn.query_row(&sql, params![id as i64], entry_from_row).optional()
+    }
+
+    /// Returns all stored entries of a title, oldest first.
+    pub fn entries_for_title(&self, title: &str) -> Result<Vec<Entry>> {
+        let sql = format!("{} WHERE t.name = ?1 ORDER BY e.created_at, e.id", SELECT_ENTRY);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![title], entry_from_row)?;
+        rows.collect()
+    }
+
+    /// Returns all stored entries of a user, oldest first.
+    pub fn entries_by_user(&self, username: &str) -> Result<Vec<Entry>> {
+        let sql = format!("{} WHERE u.username = ?1 ORDER BY e.created_at, e.id", SELECT_ENTRY);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![username], entry_from_row)?;
+        rows.collect()
+    }
+
+    /// Returns the entries of a user written between two dates (inclusive), oldest first.
+    ///
+    /// Entry dates are compared in the site's local time, as written in `Entry::date`.
+    pub fn entries_by_user_between(&self, username: &str, from: NaiveDateTime, to: NaiveDateTime) -> Result<Vec<Entry>> {
+        let sql = format!(
+            "{} WHERE u.username = ?1 AND e.created_at BETWEEN ?2 AND ?3 ORDER BY e.created_at, e.id",
+            SELECT_ENTRY
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![username, format_timestamp(from), format_timestamp(to)], entry_from_row)?;
+        rows.collect()
+    }
+
+    /// Returns the previous versions of an entry, oldest first.
+    pub fn revisions(&self, entry_id: u64) -> Result<Vec<EntryRevision>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT entry_id, content, date, replaced_at FROM entry_revisions WHERE entry_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![entry_id as i64], |row| {
+            let replaced_at: String = row.get(3)?;
+            Ok(EntryRevision {
+                entry_id: row.get::<_, i64>(0)? as u64,
+                content: row.get(1)?,
+                date: row.get(2)?,
+                replaced_at: parse_timestamp(&replaced_at, 3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Returns the names of all stored titles in alphabetical order.
+    pub fn titles(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT name FROM titles ORDER BY name")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// Returns when a title was last scraped, in UTC.
+    pub fn last_scraped(&self, title: &str) -> Result<Option<NaiveDateTime>> {
+        let value: Option<String> = self.conn
+            .query_row("SELECT last_scraped FROM titles WHERE name = ?1", params![title], |row| row.get(0))
+            .optional()?;
+        value.map(|v| parse_timestamp(&v, 0)).transpose()
+    }
+
+    /// Returns the number of stored entries.
+    pub fn entry_count(&self) -> Result<usize> {
+        self.conn.query_row("SELECT COUNT(*) FROM entries", [], |row| row.get::<_, i64>(0).map(|n| n as usize))
+    }
+}
+
+impl EntrySink for SqliteStore {
+    type Error = rusqlite::Error;
+
+    fn consume(&mut self, entries: &[Entry]) -> Result<()> {
+        self.save(entries).map(|_| ())
+    }
+}
+
+/// Inserts a title or user if needed, refreshes its scrape time and returns its row id.
+fn upsert_named(conn: &Connection, table: &str, column: &str, value: &str, now: &str) -> Result<i64> {
+    conn.execute(
+        &format!(
+            "INSERT INTO {table} ({column}, first_scraped, last_scraped) VALUES (?1, ?2, ?2)
+             ON CONFLICT({column}) DO UPDATE SET last_scraped = excluded.last_scraped"
+        ),
+        params![value, now],
+    )?;
+    conn.query_row(&format!("SELECT id FROM {table} WHERE {column} = ?1"), params![value], |row| row.get(0))
+}
+
+fn entry_from_row(row: &Row) -> Result<Entry> {
+    Ok(Entry {
+        content: row.get(0)?,
+        date: row.get(1)?,
+        username: row.get(2)?,
+        id: row.get::<_, i64>(3)? as u64,
+        title: row.get(4)?,
+        favorites: row.get(5)?,
+        links: Vec::new(),
+    })
+}
+
+fn format_timestamp(time: NaiveDateTime) -> String {
+    time.format(TIMESTAMP_FORMAT).to_string()
+}
+
+fn parse_timestamp(value: &str, column: usize) -> Result<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(column, rusqlite::types::Type::Text, Box::new(e)))
+}